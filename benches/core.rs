@@ -0,0 +1,99 @@
+//! Benchmarks for the rules engine and every strategy, on a fixed set of positions so the
+//! results can be compared across commits. Run with `cargo bench`, or eg:
+//! `cargo bench -- next_play/alphabeta` for just one group.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use desdemona::strategies::{from_name, registry, ExternalEngine};
+use desdemona::{parse_transcript, Game};
+use std::hint::black_box;
+
+/// One complete game, played to the end without passes, shared with the alphabeta tests. Each
+/// position below is a prefix of it.
+const TRANSCRIPT: &str = include_str!("../tests/fixtures/game.txt");
+
+/// The positions benchmarked, named by game phase, and how many plays into [TRANSCRIPT] each is.
+const PHASES: [(&str, usize); 3] = [("opening", 4), ("midgame", 24), ("endgame", 48)];
+
+fn positions() -> Vec<(&'static str, Game)> {
+    let plays = parse_transcript(TRANSCRIPT.trim()).expect("a valid transcript");
+    PHASES
+        .iter()
+        .map(|&(phase, length)| {
+            let game = Game::from_transcript(&plays[..length]).expect("a legal transcript");
+            (phase, game)
+        })
+        .collect()
+}
+
+fn rules(c: &mut Criterion) {
+    let positions = positions();
+
+    let mut group = c.benchmark_group("valid_moves");
+    for (phase, game) in &positions {
+        group.bench_function(*phase, |b| {
+            b.iter(|| black_box(game).valid_moves(game.turn).count())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("flips_for");
+    for (phase, game) in &positions {
+        let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+        group.bench_function(*phase, |b| {
+            b.iter(|| {
+                for &index in &moves {
+                    black_box(black_box(game).flips_for(index));
+                }
+            })
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("play");
+    for (phase, game) in &positions {
+        let index = game.valid_moves(game.turn).next().expect("a valid move");
+        group.bench_function(*phase, |b| {
+            b.iter_batched_ref(
+                || game.clone(),
+                |game| game.play(black_box(index)),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("clone");
+    for (phase, game) in &positions {
+        group.bench_function(*phase, |b| b.iter(|| black_box(game).clone()));
+    }
+    group.finish();
+
+    let plays = parse_transcript(TRANSCRIPT.trim()).expect("a valid transcript");
+    c.bench_function("from_transcript", |b| {
+        b.iter(|| Game::from_transcript(black_box(&plays)))
+    });
+}
+
+fn strategies(c: &mut Criterion) {
+    let positions = positions();
+
+    // everything registered, except engines running in another process
+    let names: Vec<&str> = registry()
+        .iter()
+        .map(|registration| registration.name)
+        .filter(|&name| name != ExternalEngine::REGISTRATION.name)
+        .collect();
+
+    for name in names {
+        let mut strategy = from_name(name).expect("a registered strategy");
+        let mut group = c.benchmark_group(format!("next_play/{}", name));
+        group.sample_size(10);
+        for (phase, game) in &positions {
+            group.bench_function(*phase, |b| b.iter(|| strategy.next_play(black_box(game))));
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, rules, strategies);
+criterion_main!(benches);
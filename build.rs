@@ -0,0 +1,70 @@
+//! Generates the static lookup tables for `src/data.rs`: the lines of play, using the same
+//! [Position] code the crate uses so they can't drift from it, and Zobrist hashing keys.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+// the build script only needs the line generation, not the rest of the type
+#[allow(dead_code)]
+#[path = "src/position.rs"]
+mod position;
+
+use position::Position;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/position.rs");
+
+    let mut data = String::from("// GENERATED BY build.rs; do not modify by hand!\n\n");
+    generate_static_index_lines(&mut data);
+    generate_zobrist_keys(&mut data);
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("data.rs");
+    fs::write(path, data).unwrap();
+}
+
+fn generate_static_index_lines(data: &mut String) {
+    data.push_str("pub const POSITION_INDEX_LINES: &[&[&[usize]]] = &[\n");
+    for index in 0..64 {
+        data.push_str("    &[");
+        for line in Position::new(index).generate_lines() {
+            write!(data, "&{:?}, ", line).unwrap();
+        }
+        data.push_str("],\n");
+    }
+    data.push_str("];\n");
+}
+
+/// Random keys for Zobrist hashing positions, one per disc color per square, and one for dark
+/// to move. They're from a fixed seed, so hashes are the same from build to build.
+fn generate_zobrist_keys(data: &mut String) {
+    // splitmix64
+    let mut seed: u64 = 0xde5d_e404_a0de_5de4;
+    let mut next = || {
+        seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+
+    data.push_str("\n/// Dark's keys, then light's, by index.\n");
+    data.push_str("pub const ZOBRIST_DISCS: [[u64; 64]; 2] = [\n");
+    for _ in 0..2 {
+        data.push_str("    [");
+        for _ in 0..64 {
+            write!(data, "{:#018x}, ", next()).unwrap();
+        }
+        data.push_str("],\n");
+    }
+    data.push_str("];\n");
+
+    writeln!(
+        data,
+        "pub const ZOBRIST_DARK_TO_MOVE: u64 = {:#018x};",
+        next()
+    )
+    .unwrap();
+}
@@ -0,0 +1,266 @@
+//! Post-game analysis: replays a transcript, searches each position with [AlphaBeta], and
+//! judges every move by how much it gave up compared to the best one.
+//!
+//! ```rust
+//! use desdemona::analysis::analyze;
+//! use desdemona::parse_transcript;
+//! use desdemona::strategies::AlphaBeta;
+//!
+//! let transcript = parse_transcript("f5,d6,c3,d3,c4").unwrap();
+//! let analysis = analyze(&transcript, &mut AlphaBeta::new(1, 0)).unwrap();
+//! println!("{}", analysis);
+//! ```
+
+use crate::strategies::{AlphaBeta, Strategy};
+use crate::{Disc, Game, Play, Position};
+use std::fmt::{self, Display, Formatter};
+
+/// How a move compares to the best one, by the score it gave up (in discs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Judgement {
+    Best,
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// The largest loss that still counts as each judgement.
+    const BEST: f32 = 0.5;
+    const INACCURACY: f32 = 4.0;
+    const MISTAKE: f32 = 10.0;
+
+    pub fn from_loss(loss: f32) -> Self {
+        if loss <= Self::BEST {
+            Judgement::Best
+        } else if loss <= Self::INACCURACY {
+            Judgement::Inaccuracy
+        } else if loss <= Self::MISTAKE {
+            Judgement::Mistake
+        } else {
+            Judgement::Blunder
+        }
+    }
+}
+
+impl Display for Judgement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Judgement::Best => "best",
+            Judgement::Inaccuracy => "inaccuracy",
+            Judgement::Mistake => "mistake",
+            Judgement::Blunder => "blunder",
+        };
+
+        // pad, so judgements line up in tables
+        f.pad(name)
+    }
+}
+
+/// The verdict on a single play.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReport {
+    /// Which play of the transcript this was, counting from one
+    pub number: usize,
+    pub player: Disc,
+    pub play: Play,
+    /// The score of the play, for the player who made it
+    pub score: f32,
+    pub best: Play,
+    pub best_score: f32,
+    pub judgement: Judgement,
+}
+
+impl MoveReport {
+    /// How much the play gave up compared to the best one.
+    pub fn loss(&self) -> f32 {
+        (self.best_score - self.score).max(0.0)
+    }
+
+    /// 100 for the best move, falling away as the loss grows.
+    pub fn accuracy(&self) -> f32 {
+        100.0 * (-self.loss() / 8.0).exp()
+    }
+}
+
+/// Every move of a game, judged. Forced passes aren't included, since there was no choice to
+/// judge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub moves: Vec<MoveReport>,
+}
+
+impl Analysis {
+    /// The average accuracy of a player's moves, if they made any.
+    pub fn accuracy(&self, player: Disc) -> Option<f32> {
+        let accuracies: Vec<f32> = self
+            .moves
+            .iter()
+            .filter(|m| m.player == player)
+            .map(MoveReport::accuracy)
+            .collect();
+
+        if accuracies.is_empty() {
+            None
+        } else {
+            Some(accuracies.iter().sum::<f32>() / accuracies.len() as f32)
+        }
+    }
+
+    /// How many of a player's moves were given a judgement.
+    pub fn count(&self, player: Disc, judgement: Judgement) -> usize {
+        self.moves
+            .iter()
+            .filter(|m| m.player == player && m.judgement == judgement)
+            .count()
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  #  Player  Play   Score  Best   Score  Judgement")?;
+        for m in &self.moves {
+            writeln!(
+                f,
+                "{:>3}  {:<6}  {:<4} {:>7.1}  {:<4} {:>7.1}  {}",
+                m.number,
+                format!("{:?}", m.player),
+                m.play.to_string(),
+                m.score,
+                m.best.to_string(),
+                m.best_score,
+                m.judgement
+            )?;
+        }
+
+        writeln!(f)?;
+        for player in [Disc::Dark, Disc::Light] {
+            let accuracy = match self.accuracy(player) {
+                Some(accuracy) => format!("{:.1}%", accuracy),
+                None => "-".to_owned(),
+            };
+
+            writeln!(
+                f,
+                "{:?} accuracy: {} ({} inaccuracies, {} mistakes, {} blunders)",
+                player,
+                accuracy,
+                self.count(player, Judgement::Inaccuracy),
+                self.count(player, Judgement::Mistake),
+                self.count(player, Judgement::Blunder),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Replays `transcript` from the start of a game, judging each play with `search`. Passes made
+/// while there were moves to play are judged too.
+pub fn analyze(transcript: &[Play], search: &mut AlphaBeta) -> Result<Analysis, String> {
+    let mut game = Game::new();
+    let mut moves = vec![];
+
+    for (n, play) in transcript.iter().enumerate() {
+        if game.is_complete {
+            return Err(format!(
+                "play {} ({}) is after the end of the game",
+                n + 1,
+                play
+            ));
+        }
+
+        let mut scored = search.score_plays(&game);
+        scored.sort_by(|a, b| b.cmp(a));
+
+        let player = game.turn;
+        match (play, scored.first()) {
+            // a forced pass, nothing to judge
+            (Play::Pass, None) => game.pass(),
+            (Play::Pass, Some(best)) => {
+                let mut passed = game.clone();
+                passed.pass();
+                let score = -search.evaluate(&passed);
+                moves.push(report(n, player, play, score, best.index, best.score));
+                game = passed;
+            }
+            (Play::Move(position), _) => {
+                let index = game
+                    .validate_move(player, position.into())
+                    .ok_or_else(|| format!("play {} ({}) is illegal", n + 1, play))?;
+
+                // the best move is always scored, so there's one for any valid move
+                let best = &scored[0];
+                let score = scored
+                    .iter()
+                    .find(|s| s.index == index)
+                    .map(|s| s.score)
+                    .unwrap_or(best.score);
+                moves.push(report(n, player, play, score, best.index, best.score));
+                game.play(index);
+            }
+        }
+    }
+
+    Ok(Analysis { moves })
+}
+
+fn report(
+    n: usize,
+    player: Disc,
+    play: &Play,
+    score: f32,
+    best: usize,
+    best_score: f32,
+) -> MoveReport {
+    let mut report = MoveReport {
+        number: n + 1,
+        player,
+        play: play.clone(),
+        score,
+        best: Play::Move(Position::new(best)),
+        best_score,
+        judgement: Judgement::Best,
+    };
+    report.judgement = Judgement::from_loss(report.loss());
+    report
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::parse_transcript;
+
+    #[test]
+    fn test_judgement() {
+        assert_eq!(Judgement::from_loss(0.0), Judgement::Best);
+        assert_eq!(Judgement::from_loss(2.0), Judgement::Inaccuracy);
+        assert_eq!(Judgement::from_loss(8.0), Judgement::Mistake);
+        assert_eq!(Judgement::from_loss(30.0), Judgement::Blunder);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let transcript = parse_transcript("f5,d6,c3,d3,c4").unwrap();
+        let analysis = analyze(&transcript, &mut AlphaBeta::new(1, 0)).unwrap();
+
+        assert_eq!(analysis.moves.len(), 5);
+        assert_eq!(analysis.moves[1].player, Disc::Light);
+        assert!(analysis.moves.iter().all(|m| m.score <= m.best_score));
+        assert!(analysis.accuracy(Disc::Dark).is_some());
+        assert!(analysis.to_string().contains("Light accuracy"));
+
+        let illegal = parse_transcript("f5,a1").unwrap();
+        assert!(analyze(&illegal, &mut AlphaBeta::new(1, 0)).is_err());
+    }
+
+    #[test]
+    fn test_voluntary_pass() {
+        let transcript = parse_transcript("f5,p").unwrap();
+        let analysis = analyze(&transcript, &mut AlphaBeta::new(1, 0)).unwrap();
+
+        assert_eq!(analysis.moves[1].play, Play::Pass);
+        assert!(analysis.moves[1].loss() >= 0.0);
+    }
+}
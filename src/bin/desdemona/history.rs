@@ -0,0 +1,139 @@
+use desdemona::{Disc, Game, Play};
+
+/// Takebacks for the interactive game. Earlier positions are rebuilt by replaying the
+/// transcript from where the game started, so passes are taken back like any other play.
+pub struct History {
+    start: Game,
+    redo: Vec<Play>,
+    remaining: Option<usize>,
+}
+
+impl History {
+    /// Starts tracking `game`, allowing `limit` takebacks (or any number, if there's no limit).
+    pub fn new(game: &Game, limit: Option<usize>) -> Self {
+        // games that can be replayed from the opening can be taken back all the way
+        let start = match Game::from_transcript(&game.transcript) {
+            Some(replayed) if replayed.board == game.board && replayed.turn == game.turn => {
+                Game::new()
+            }
+            _ => game.clone(),
+        };
+
+        Self {
+            start,
+            redo: vec![],
+            remaining: limit,
+        }
+    }
+
+    /// Takes back plays up to and including the last one made by a human, so against the
+    /// computer this undoes its reply along with the human's move.
+    pub fn undo(&mut self, game: &mut Game, is_human: impl Fn(Disc) -> bool) -> Result<(), String> {
+        if self.remaining == Some(0) {
+            return Err("No takebacks allowed.".to_owned());
+        }
+
+        let plays = &game.transcript[self.start.transcript.len()..];
+
+        // work out who made each play
+        let mut replayed = self.start.clone();
+        let movers: Vec<Disc> = plays
+            .iter()
+            .map(|play| {
+                let mover = replayed.turn;
+                replay(&mut replayed, play);
+                mover
+            })
+            .collect();
+
+        let keep = movers
+            .iter()
+            .rposition(|&disc| is_human(disc))
+            .ok_or("Nothing to take back.")?;
+
+        // the redo stack pops the earliest play first
+        self.redo.extend(plays[keep..].iter().rev().cloned());
+
+        let mut rebuilt = self.start.clone();
+        for play in &plays[..keep] {
+            replay(&mut rebuilt, play);
+        }
+        *game = rebuilt;
+
+        self.remaining = self.remaining.map(|n| n - 1);
+        Ok(())
+    }
+
+    /// Replays the next play that was taken back, along with any replies up to the next human
+    /// turn.
+    pub fn redo(&mut self, game: &mut Game, is_human: impl Fn(Disc) -> bool) -> Result<(), String> {
+        let play = self.redo.pop().ok_or("Nothing to redo.")?;
+        replay(game, &play);
+
+        while !game.is_complete && !is_human(game.turn) {
+            match self.redo.pop() {
+                Some(play) => replay(game, &play),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A new play makes the taken back plays irrelevant.
+    pub fn played(&mut self) {
+        self.redo.clear();
+    }
+}
+
+/// Repeats a play from the transcript, which is known to be valid.
+fn replay(game: &mut Game, play: &Play) {
+    match play {
+        Play::Move(position) => {
+            let valid_move = game
+                .validate_move(game.turn, position.into())
+                .expect("plays in the transcript are valid");
+            game.play(valid_move);
+        }
+        Play::Pass => game.pass(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use desdemona::parse_transcript;
+
+    #[test]
+    fn test_undo_redo() {
+        let plays = parse_transcript("d3,c5,f6,f5").unwrap();
+        let mut game = Game::from_transcript(&plays).unwrap();
+        let mut history = History::new(&game, None);
+
+        // dark is the human, so taking back light's f5 also takes back dark's f6
+        history.undo(&mut game, |d| d == Disc::Dark).unwrap();
+        assert_eq!(game.transcript, plays[..2]);
+        assert_eq!(game.turn, Disc::Dark);
+
+        history.undo(&mut game, |d| d == Disc::Dark).unwrap();
+        assert!(game.transcript.is_empty());
+        assert!(history.undo(&mut game, |d| d == Disc::Dark).is_err());
+
+        history.redo(&mut game, |d| d == Disc::Dark).unwrap();
+        assert_eq!(game.transcript, plays[..2]);
+        history.redo(&mut game, |d| d == Disc::Dark).unwrap();
+        assert_eq!(game.transcript, plays);
+        assert!(history.redo(&mut game, |d| d == Disc::Dark).is_err());
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut game = Game::from_transcript(&parse_transcript("d3,c5,f6,f5").unwrap()).unwrap();
+        let mut history = History::new(&game, Some(1));
+
+        history.undo(&mut game, |_| true).unwrap();
+        assert_eq!(game.transcript.len(), 3);
+        assert!(history.undo(&mut game, |_| true).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+use desdemona::Position;
+use std::io::{self, Write};
+use text_io::read;
+
+#[derive(Debug)]
+pub enum Input {
+    Move(Position),
+    Pass,
+    Transcript,
+    Hint,
+    ValidMoves,
+    Eval,
+    Undo,
+    Redo,
+    Save(String),
+    Load(String),
+    Quit,
+}
+
+pub fn prompt_for_play() -> Option<Input> {
+    // show a prompt
+    print!("> ");
+    io::stdout().flush().unwrap();
+
+    // read the whole line, since some commands take an argument
+    let line: String = read!("{}\n");
+    parse_input(&line)
+}
+
+/// Parses a line of input, returning `None` if it doesn't make sense.
+pub fn parse_input(line: &str) -> Option<Input> {
+    const X_VALUES: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+    const Y_VALUES: [usize; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    let (input, argument) = match line.trim().split_once(char::is_whitespace) {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (line.trim(), ""),
+    };
+
+    // whole word commands first, since they'd otherwise look like coordinates
+    match input.to_ascii_lowercase().as_str() {
+        "eval" => return Some(Input::Eval),
+        "redo" => return Some(Input::Redo),
+        "save" if !argument.is_empty() => return Some(Input::Save(argument.to_owned())),
+        "load" if !argument.is_empty() => return Some(Input::Load(argument.to_owned())),
+        "save" | "load" => return None,
+        _ => {}
+    }
+
+    // take the first character as x, convert to usize
+    let raw_x = input.chars().next()?;
+    let lower_x = raw_x.to_ascii_lowercase();
+
+    // catch 'q'uit or 'p'ass or look up the X index
+    let x = match lower_x {
+        'p' => return Some(Input::Pass),
+        'q' => return Some(Input::Quit),
+        't' => return Some(Input::Transcript),
+        'h' => return Some(Input::Hint),
+        'v' => return Some(Input::ValidMoves),
+        'u' => return Some(Input::Undo),
+        _ => X_VALUES.iter().position(|c| c == &lower_x)?,
+    };
+
+    // second character as y, convert to usize
+    let raw_y = input.chars().nth(1)?;
+    let natural_y = raw_y.to_digit(10)? as usize;
+    let y = Y_VALUES.iter().position(|i| i == &natural_y)?;
+
+    Some(Input::Move(Position::from_xy(x, y)))
+}
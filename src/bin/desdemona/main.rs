@@ -0,0 +1,437 @@
+mod history;
+mod input;
+mod players;
+mod puzzles;
+mod remote;
+mod saved;
+mod tui;
+
+use clap::{App, ArgMatches};
+use desdemona::analysis::analyze;
+use desdemona::network::DEFAULT_PORT;
+use desdemona::puzzle;
+use desdemona::render::{Renderer, Theme};
+use desdemona::strategies::*;
+use desdemona::{parse_transcript, Disc, Game, Play, Position};
+use history::History;
+use input::{prompt_for_play, Input};
+use players::{pondering, Player, Players};
+use saved::SavedGame;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// What to do after a player's turn.
+enum Flow {
+    Continue,
+    Undo,
+    Redo,
+    Save(String),
+    Load(String),
+    Quit,
+}
+
+fn main() {
+    let config = get_args();
+
+    if let Some(transcript) = config.value_of("analyze") {
+        print_analysis(transcript);
+        return;
+    }
+
+    if let Some(count) = config.value_of("make-puzzles") {
+        match count.parse() {
+            Ok(count) => {
+                for _ in 0..count {
+                    println!("{}", puzzle::generate(puzzles::MAX_EMPTY));
+                }
+            }
+            Err(_) => println!("The number of puzzles should be a number."),
+        }
+        return;
+    }
+
+    let (mut game, mut players) = match setup(&config) {
+        Ok(setup) => setup,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let hint_spec = config.value_of("hint").unwrap_or("monte");
+    let mut hint_strategy = match from_name(hint_spec) {
+        Ok(s) => s,
+        Err(e) => {
+            println!("{} -- available strategies are:\n{}", e, registry().help());
+            return;
+        }
+    };
+
+    let delay = match config.value_of("delay").unwrap_or("1000").parse() {
+        Ok(millis) => Duration::from_millis(millis),
+        Err(_) => {
+            println!("The delay should be a number of milliseconds.");
+            return;
+        }
+    };
+
+    let movetime = match config.value_of("movetime").map(str::parse).transpose() {
+        Ok(millis) => millis.map(Duration::from_millis),
+        Err(_) => {
+            println!("The move time should be a number of milliseconds.");
+            return;
+        }
+    };
+
+    let renderer = match renderer_from_args(&config) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
+
+    if config.is_present("host") || config.is_present("join") {
+        let role = match config.value_of("join") {
+            Some(address) => Ok(remote::Role::Join(address.to_owned())),
+            None => config
+                .value_of("port")
+                .map_or(Ok(DEFAULT_PORT), str::parse)
+                .map_err(|_| "The port should be a number.".to_owned())
+                .and_then(|port| Ok(remote::Role::Host(port, players::color_from_args(&config)?))),
+        };
+
+        match role.map(|role| remote::run(role, &renderer)) {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => println!("Network error: {}", e),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    if config.is_present("puzzle") {
+        match puzzles::load(config.value_of("puzzles")) {
+            Ok(puzzles) => puzzles::run(&puzzles, &renderer),
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    let takebacks = if config.is_present("rated") {
+        Some(0)
+    } else {
+        match config.value_of("takebacks").map(str::parse).transpose() {
+            Ok(limit) => limit,
+            Err(_) => {
+                println!("The takeback limit should be a number.");
+                return;
+            }
+        }
+    };
+    let mut history = History::new(&game, takebacks);
+    let ponder = config.is_present("ponder");
+
+    if config.is_present("tui") {
+        let outcome = tui::run(
+            &mut game,
+            &mut players,
+            hint_strategy.as_mut(),
+            delay,
+            movetime,
+            ponder,
+            renderer.theme,
+        );
+        match outcome {
+            Ok(tui::Outcome::Complete) => {
+                println!("{}", renderer.game(&game));
+                print_transcript(&game.transcript);
+                println!("Good game!");
+            }
+            Ok(tui::Outcome::Quit) => {
+                print_transcript(&game.transcript);
+                println!("See ya!");
+            }
+            Err(e) => println!("Terminal error: {}", e),
+        }
+        return;
+    }
+
+    println!("Desdemona!\n\n{}\n", players.describe());
+    if players.dark.is_human() || players.light.is_human() {
+        println!("Place a disc with a coordinate (eg: \"a2\"), or pass with \"p\".\nFor a hint, type \"h\"; to see your valid moves, \"v\"; to evaluate the position, \"eval\".\nTo take back your last move, type \"u\"; to play it again, \"redo\".\nTo get a transcript of the game, type \"t\"; to save it, \"save <file>\", and to pick up a saved game, \"load <file>\".\nTo quit, \"q\".\nHave fun!\n");
+    }
+
+    while !game.is_complete {
+        println!("{}", renderer.game(&game));
+
+        let flow = match players.get_mut(game.turn) {
+            Player::Human if ponder => {
+                let position = game.clone();
+                pondering(players.get(game.turn.opposite()), &position, || {
+                    human_turn(&mut game, hint_strategy.as_mut(), &renderer)
+                })
+            }
+            Player::Human => human_turn(&mut game, hint_strategy.as_mut(), &renderer),
+            Player::Computer(strategy, _) => {
+                computer_turn(&mut game, strategy.as_mut(), delay, movetime)
+            }
+        };
+
+        match flow {
+            Flow::Continue => history.played(),
+            Flow::Undo => {
+                if let Err(e) = history.undo(&mut game, |disc| players.get(disc).is_human()) {
+                    println!("{}", e);
+                }
+            }
+            Flow::Redo => {
+                if let Err(e) = history.redo(&mut game, |disc| players.get(disc).is_human()) {
+                    println!("{}", e);
+                }
+            }
+            Flow::Save(path) => match SavedGame::new(&game, &players).write(&path) {
+                Ok(()) => println!("Saved to {}", path),
+                Err(e) => println!("{}", e),
+            },
+            Flow::Load(path) => match SavedGame::read(&path).and_then(|s| Ok((s.players()?, s))) {
+                Ok((loaded_players, saved)) => {
+                    game = saved.game;
+                    players = loaded_players;
+                    history = History::new(&game, takebacks);
+                    println!("Loaded {}\n\n{}\n", path, players.describe());
+                }
+                Err(e) => println!("{}", e),
+            },
+            Flow::Quit => {
+                print_transcript(&game.transcript);
+                println!("See ya!");
+                return;
+            }
+        }
+    }
+
+    println!("{}", renderer.game(&game));
+    print_transcript(&game.transcript);
+    println!("To see how it went, try desdemona --analyze <transcript>\nGood game!");
+}
+
+/// Starts a new game, or resumes the one saved in `--load`. Players come from the saved game
+/// unless `--dark` or `--light` override them.
+fn setup(config: &ArgMatches) -> Result<(Game, Players), String> {
+    let path = match config.value_of("load") {
+        Some(path) => path,
+        None => return Ok((Game::new(), Players::from_args(config)?)),
+    };
+
+    let saved = SavedGame::read(path)?;
+    let mut players = saved.players()?;
+    if let Some(spec) = config.value_of("dark") {
+        players.dark = Player::from_name(spec)?;
+    }
+    if let Some(spec) = config.value_of("light") {
+        players.light = Player::from_name(spec)?;
+    }
+
+    Ok((saved.game, players))
+}
+
+/// Builds the renderer for line mode from `--theme`, `--no-coordinates`, and `--show-moves`.
+fn renderer_from_args(config: &ArgMatches) -> Result<Renderer, String> {
+    let theme = match config.value_of("theme").unwrap_or("auto") {
+        "auto" => Theme::detect(),
+        name => Theme::from_name(name).ok_or_else(|| {
+            let names: Vec<&str> = Theme::ALL.iter().map(|t| t.name).collect();
+            format!("Unknown theme {} -- try auto, {}", name, names.join(", "))
+        })?,
+    };
+
+    Ok(Renderer {
+        theme,
+        coordinates: !config.is_present("no-coordinates"),
+        legal_moves: config.is_present("show-moves"),
+    })
+}
+
+/// Loops until the human has played a valid move, passed, or quit.
+fn human_turn(game: &mut Game, hint_strategy: &mut dyn Strategy, renderer: &Renderer) -> Flow {
+    loop {
+        let input = match prompt_for_play() {
+            None => {
+                // invalid input, try again
+                println!("What?");
+                continue;
+            }
+            Some(input) => input,
+        };
+
+        match input {
+            Input::Quit => return Flow::Quit,
+            Input::Transcript => print_transcript(&game.transcript),
+            Input::Hint => print_hints(game, hint_strategy),
+            Input::ValidMoves => {
+                let valid_moves: Vec<usize> = game.valid_moves(game.turn).collect();
+                println!("{}", renderer.board(&game.board, &valid_moves));
+            }
+            Input::Eval => print_eval(game, hint_strategy),
+            Input::Undo => return Flow::Undo,
+            Input::Redo => return Flow::Redo,
+            Input::Save(path) => return Flow::Save(path),
+            Input::Load(path) => return Flow::Load(path),
+            Input::Pass => {
+                game.pass();
+                return Flow::Continue;
+            }
+            Input::Move(position) => match game.validate_move(game.turn, position.into()) {
+                Some(valid_move) => {
+                    // it's a good move, do it
+                    game.play(valid_move);
+                    return Flow::Continue;
+                }
+                None => {
+                    // can't move there
+                    println!("Invalid move.");
+                }
+            },
+        }
+    }
+}
+
+/// Asks the computer for its play, printing a dot for each step of its search, and stopping
+/// it after `movetime` if there is one.
+fn computer_turn(
+    game: &mut Game,
+    strategy: &mut dyn Strategy,
+    delay: Duration,
+    movetime: Option<Duration>,
+) -> Flow {
+    print!("Desdemona ({:?})...", game.turn);
+    io::stdout().flush().unwrap();
+
+    // insert a small delay so it's clear that the computer has played, otherwise
+    // it appears instantaneously.
+    std::thread::sleep(delay);
+
+    let search = Search::new().with_info(|_| {
+        print!(".");
+        io::stdout().flush().unwrap();
+    });
+    let play = match movetime {
+        Some(limit) => search.stop_after(limit, || strategy.search_play(game, &search)),
+        None => strategy.search_play(game, &search),
+    };
+
+    match play {
+        Some(vm) => {
+            game.play(vm);
+            println!(" {}", Position::new(vm));
+        }
+        None => {
+            game.pass();
+            println!(" pass");
+        }
+    };
+
+    Flow::Continue
+}
+
+/// Shows the candidate moves for the current player, ranked by the hint strategy.
+fn print_hints(game: &Game, strategy: &mut dyn Strategy) {
+    let mut scored_plays = strategy.score_plays(game);
+    if scored_plays.is_empty() {
+        println!("No valid moves; you'll have to pass.");
+        return;
+    }
+
+    scored_plays.sort_by(|a, b| b.cmp(a));
+
+    println!("Hints from {}:", strategy.name());
+    for (rank, scored_play) in scored_plays.iter().enumerate() {
+        println!(
+            "{:>3}. {}  {:.3}",
+            rank + 1,
+            Position::new(scored_play.index),
+            scored_play.score
+        );
+    }
+}
+
+/// Summarizes the position: material, mobility, and the hint strategy's favorite move.
+fn print_eval(game: &Game, strategy: &mut dyn Strategy) {
+    let dark_moves = game.valid_moves(Disc::Dark).count();
+    let light_moves = game.valid_moves(Disc::Light).count();
+
+    println!(
+        "Dark: {} discs, {} moves. Light: {} discs, {} moves. Empty: {}",
+        game.dark, dark_moves, game.light, light_moves, game.empty
+    );
+
+    match strategy.score_plays(game).into_iter().max() {
+        Some(best) => println!(
+            "{} rates {} as the best move for {:?}, scoring {:.3}",
+            strategy.name(),
+            Position::new(best.index),
+            game.turn,
+            best.score
+        ),
+        None => println!("{:?} has no valid moves.", game.turn),
+    }
+}
+
+/// Judges every move of a game, searching each position with [AlphaBeta].
+fn print_analysis(transcript: &str) {
+    let analysis =
+        parse_transcript(transcript).and_then(|plays| analyze(&plays, &mut AlphaBeta::default()));
+
+    match analysis {
+        Ok(analysis) => print!("{}", analysis),
+        Err(e) => println!("Couldn't analyze the game: {}", e),
+    }
+}
+
+fn print_transcript(transcript: &[Play]) {
+    println!(
+        "Transcript: {}",
+        transcript
+            .iter()
+            .map(|p| format!("{}", p))
+            .collect::<Vec<String>>()
+            .join(",")
+    );
+}
+
+fn get_args() -> ArgMatches<'static> {
+    let about = format!(
+        "Would you like to play a game? Available opponent strategies (and their parameters) are:\n{}\nParameters are given in parentheses, eg: monte(rounds=2000,threads=4)",
+        registry().help()
+    );
+
+    App::new("desdemona")
+        .version("0.1")
+        .author("Peat Bakke <peat@peat.org>")
+        .long_about(about.as_str())
+        .args_from_usage(
+            "-s, --strategy=[STRATEGY]       'Determine the computer's strategy'
+            -c, --color=[COLOR]             'Play as dark, light, or random (default dark)'
+            --dark=[PLAYER]                 'Who plays dark: human, or a strategy'
+            --light=[PLAYER]                'Who plays light: human, or a strategy'
+            --delay=[MILLISECONDS]          'How long the computer pauses before playing (default 1000)'
+            --hint=[STRATEGY]               'The strategy used for hints and evaluation (default monte)'
+            --movetime=[MILLISECONDS]       'Stop the computer thinking after this long, and play its best move so far (not every strategy can stop early)'
+            --ponder                        'Let the computer think on your time, for strategies that can (eg: alphabeta)'
+            --tui                           'Play in a full screen terminal UI, using the arrow keys or mouse'
+            --theme=[THEME]                 'How to draw the board: auto, color, ascii, unicode-dark, or unicode-light (default auto)'
+            --no-coordinates                'Leave the coordinate labels off the board'
+            --show-moves                    'Mark the valid moves on the board every turn'
+            --load=[FILE]                   'Resume a game saved with the save command'
+            --takebacks=[LIMIT]             'How many moves you can take back (default unlimited)'
+            --rated                         'Play without takebacks'
+            --analyze=[TRANSCRIPT]          'Judge every move of a finished game, then exit'
+            --puzzle                        'Solve endgame puzzles instead of playing a game'
+            --puzzles=[FILE]                'Where to find puzzles for --puzzle (by default, some are made up)'
+            --make-puzzles=[COUNT]          'Print new puzzles, found in random games, then exit'
+            --host                          'Host a game for someone to join over the network, playing --color'
+            --port=[PORT]                   'The port to host on (default 7474)'
+            --join=[ADDRESS]                'Join a game hosted at an address, like 10.0.0.5 or 10.0.0.5:7474'",
+        )
+        .get_matches()
+}
@@ -0,0 +1,149 @@
+use clap::ArgMatches;
+use desdemona::strategies::*;
+use desdemona::{Disc, Game};
+use rand::prelude::*;
+use std::thread;
+
+/// Who is making the plays for one side of the board.
+pub enum Player {
+    Human,
+    /// A strategy, along with the configuration it was built from
+    Computer(Box<dyn Strategy>, Config),
+}
+
+impl Player {
+    /// Parses `human`, or any strategy specification such as `monte(rounds=500)`.
+    pub fn from_name(spec: &str) -> Result<Self, String> {
+        if spec.trim().eq_ignore_ascii_case("human") {
+            return Ok(Player::Human);
+        }
+
+        let computer = Config::parse(spec)
+            .and_then(|config| Ok(Player::Computer(from_config(&config)?, config)));
+
+        match computer {
+            Ok(player) => Ok(player),
+            Err(e) => Err(format!(
+                "{} -- try human, or one of these strategies:\n{}",
+                e,
+                registry().help()
+            )),
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        matches!(self, Player::Human)
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Player::Human => "human",
+            Player::Computer(strategy, _) => strategy.name(),
+        }
+    }
+
+    /// How to set up this player again with [Player::from_name()].
+    pub fn spec(&self) -> String {
+        match self {
+            Player::Human => "human".to_owned(),
+            Player::Computer(_, config) => config.to_string(),
+        }
+    }
+}
+
+/// The players for both sides of the board.
+pub struct Players {
+    pub dark: Player,
+    pub light: Player,
+}
+
+impl Players {
+    /// Works out who plays which side. The human plays `--color` (dark unless told otherwise)
+    /// against `--strategy`, and `--dark` or `--light` override either side with `human` or a
+    /// strategy, for hotseat games or watching two computers play.
+    pub fn from_args(config: &ArgMatches) -> Result<Self, String> {
+        let human = color_from_args(config)?;
+        let computer = config.value_of("strategy").unwrap_or("minimize");
+
+        let spec_for = |disc: Disc, flag: &str| match config.value_of(flag) {
+            Some(spec) => spec,
+            None if disc == human => "human",
+            None => computer,
+        };
+
+        Ok(Self {
+            dark: Player::from_name(spec_for(Disc::Dark, "dark"))?,
+            light: Player::from_name(spec_for(Disc::Light, "light"))?,
+        })
+    }
+
+    pub fn get(&self, disc: Disc) -> &Player {
+        match disc {
+            Disc::Dark => &self.dark,
+            Disc::Light => &self.light,
+        }
+    }
+
+    pub fn get_mut(&mut self, disc: Disc) -> &mut Player {
+        match disc {
+            Disc::Dark => &mut self.dark,
+            Disc::Light => &mut self.light,
+        }
+    }
+
+    /// Describes the matchup for the welcome message.
+    pub fn describe(&self) -> String {
+        match (self.dark.is_human(), self.light.is_human()) {
+            (true, true) => "Two humans! Take turns at the keyboard.".to_owned(),
+            (true, false) => format!(
+                "Computer Strategy: {}\n\nYou are the dark discs.",
+                self.light.name()
+            ),
+            (false, true) => format!(
+                "Computer Strategy: {}\n\nYou are the light discs.",
+                self.dark.name()
+            ),
+            (false, false) => format!(
+                "{} (dark) vs {} (light)",
+                self.dark.name(),
+                self.light.name()
+            ),
+        }
+    }
+}
+
+/// Runs `turn`, a human's turn in `game`, while `opponent` thinks about the position on
+/// another thread if it's a computer (see [Strategy::ponder()]), and stops it afterwards.
+pub fn pondering<T>(opponent: &Player, game: &Game, turn: impl FnOnce() -> T) -> T {
+    let strategy = match opponent {
+        Player::Computer(strategy, _) => strategy.as_ref(),
+        Player::Human => return turn(),
+    };
+
+    let search = Search::new();
+    thread::scope(|scope| {
+        scope.spawn(|| strategy.ponder(game, &search));
+        let outcome = turn();
+        search.stop();
+        outcome
+    })
+}
+
+/// The color the human plays, from `--color`: dark (the default), light, or random.
+pub fn color_from_args(config: &ArgMatches) -> Result<Disc, String> {
+    match config.value_of("color").unwrap_or("dark") {
+        "dark" => Ok(Disc::Dark),
+        "light" => Ok(Disc::Light),
+        "random" => {
+            if thread_rng().gen_bool(0.5) {
+                Ok(Disc::Dark)
+            } else {
+                Ok(Disc::Light)
+            }
+        }
+        other => Err(format!(
+            "Unknown color {} -- try dark, light, or random",
+            other
+        )),
+    }
+}
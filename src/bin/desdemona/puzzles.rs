@@ -0,0 +1,84 @@
+use crate::input::{prompt_for_play, Input};
+use desdemona::puzzle::{generate, parse_puzzles, Puzzle};
+use desdemona::render::Renderer;
+use std::fs;
+use std::io::{self, Write};
+
+/// How many puzzles to make up when there's no puzzle file.
+const GENERATED: usize = 5;
+
+/// The most empty squares in a generated puzzle; more makes harder puzzles, which take longer
+/// to find.
+pub const MAX_EMPTY: usize = 12;
+
+/// Reads puzzles from a file, checking each one has a single winning move, or makes some up.
+pub fn load(path: Option<&str>) -> Result<Vec<Puzzle>, String> {
+    let Some(path) = path else {
+        print!("Setting up some puzzles...");
+        io::stdout().flush().unwrap();
+        let puzzles = (0..GENERATED).map(|_| generate(MAX_EMPTY)).collect();
+        println!(" done.\n");
+        return Ok(puzzles);
+    };
+
+    let text = fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+    let puzzles = parse_puzzles(&text).map_err(|e| format!("Couldn't load {}: {}", path, e))?;
+
+    for (n, puzzle) in puzzles.iter().enumerate() {
+        puzzle
+            .verify()
+            .map_err(|e| format!("Puzzle {} in {} is broken: {}", n + 1, path, e))?;
+    }
+
+    Ok(puzzles)
+}
+
+/// Presents each puzzle in turn, checking the answers with the endgame solver, and keeps score.
+pub fn run(puzzles: &[Puzzle], renderer: &Renderer) {
+    let mut solved = 0;
+    let mut attempted = 0;
+
+    for (n, puzzle) in puzzles.iter().enumerate() {
+        println!(
+            "Puzzle {} of {}: {:?} to play and win.\n",
+            n + 1,
+            puzzles.len(),
+            puzzle.turn
+        );
+        println!("{}", renderer.board(&puzzle.board, &[]));
+
+        let game = puzzle.game();
+        let answer = loop {
+            match prompt_for_play() {
+                Some(Input::Quit) => break None,
+                Some(Input::ValidMoves) => {
+                    let valid_moves: Vec<usize> = game.valid_moves(game.turn).collect();
+                    println!("{}", renderer.board(&game.board, &valid_moves));
+                }
+                Some(Input::Move(position))
+                    if game.validate_move(game.turn, position.into()).is_some() =>
+                {
+                    break Some(position)
+                }
+                Some(Input::Move(_)) => println!("Invalid move."),
+                _ => println!(
+                    "Place a disc to answer (eg: \"b1\"), \"v\" to see the valid moves, or \"q\" to stop."
+                ),
+            }
+        };
+
+        let Some(answer) = answer else {
+            break;
+        };
+
+        attempted += 1;
+        if puzzle.is_solution(answer.into()) {
+            solved += 1;
+            println!("Correct! {} wins.\n", answer);
+        } else {
+            println!("Not quite -- {} is the winning move.\n", puzzle.solution);
+        }
+    }
+
+    println!("You solved {} of {} puzzles.", solved, attempted);
+}
@@ -0,0 +1,312 @@
+use crate::input::{parse_input, Input};
+use crate::print_transcript;
+use desdemona::network::{Message, NetworkGame, Peer, DEFAULT_PORT};
+use desdemona::render::Renderer;
+use desdemona::{Disc, Play};
+use std::io::{self, BufRead};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// How many times the joining player tries to get back into the game after losing the
+/// connection, and how long they wait between tries.
+const RECONNECT_ATTEMPTS: usize = 10;
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Which end of the connection this is.
+pub enum Role {
+    /// Hosting on a port, playing a color
+    Host(u16, Disc),
+    /// Joining a host at an address
+    Join(String),
+}
+
+/// Everything the game loop waits on: lines typed here, and messages from the other player.
+enum Event {
+    Local(String),
+    Remote(Result<Message, String>),
+    Disconnected,
+}
+
+/// Plays a game against someone on another machine. The host's copy of the game is the
+/// official one: when the joining player reconnects, they pick up the host's transcript.
+pub fn run(role: Role, renderer: &Renderer) -> io::Result<()> {
+    let (events, receiver) = mpsc::channel();
+
+    // stdin is read on its own thread, so chat can arrive while we wait for a move
+    let local = events.clone();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines().map_while(Result::ok) {
+            if local.send(Event::Local(line)).is_err() {
+                return;
+            }
+        }
+        let _ = local.send(Event::Local("q".to_owned()));
+    });
+
+    let listener = match role {
+        Role::Host(port, _) => {
+            let listener = TcpListener::bind(("0.0.0.0", port))?;
+            println!(
+                "Hosting on port {}. Waiting for an opponent to join...",
+                port
+            );
+            Some(listener)
+        }
+        Role::Join(_) => None,
+    };
+
+    let (mut peer, mut network_game) = match (&role, &listener) {
+        (Role::Host(_, color), Some(listener)) => {
+            let mut peer = Peer::accept(listener)?;
+            let network_game = NetworkGame::new(*color);
+            peer.send(&network_game.welcome())?;
+            listen(&peer, &events)?;
+            (peer, network_game)
+        }
+        (Role::Join(address), _) => {
+            let peer = Peer::connect(with_port(address))?;
+            listen(&peer, &events)?;
+            let Some(network_game) = wait_for_game(&receiver) else {
+                return Ok(());
+            };
+            (peer, network_game)
+        }
+        _ => unreachable!(),
+    };
+
+    println!(
+        "Connected! You are playing {:?}. Type a move to play it, \"say <message>\" to chat, or \"resign\" to give up.\n",
+        network_game.local
+    );
+    show(&network_game, renderer);
+
+    loop {
+        // passes are forced, so there's no need to ask
+        if network_game.is_local_turn()
+            && network_game
+                .game
+                .valid_moves(network_game.local)
+                .next()
+                .is_none()
+        {
+            println!("You have no moves, so you pass.");
+            send(&mut peer, network_game.play_local(&Play::Pass));
+            show(&network_game, renderer);
+        }
+
+        if network_game.is_over() {
+            finish(&network_game, renderer);
+            return Ok(());
+        }
+
+        match receiver.recv().expect("stdin thread outlives the game") {
+            Event::Local(line) => {
+                local_input(&line, &mut network_game, &mut peer, renderer);
+            }
+            Event::Remote(Ok(message)) => {
+                remote_message(message, &mut network_game, &mut peer, renderer);
+            }
+            Event::Remote(Err(e)) => println!("Garbled message from your opponent: {}", e),
+            Event::Disconnected => {
+                println!("Lost the connection to your opponent.");
+                match (&role, &listener) {
+                    (Role::Host(..), Some(listener)) => {
+                        println!("Waiting for them to reconnect (Ctrl-C to give up)...");
+                        peer = Peer::accept(listener)?;
+                        peer.send(&network_game.welcome())?;
+                        listen(&peer, &events)?;
+                    }
+                    (Role::Join(address), _) => {
+                        peer = reconnect(address)?;
+                        listen(&peer, &events)?;
+                        match wait_for_game(&receiver) {
+                            Some(rejoined) => network_game = rejoined,
+                            None => return Ok(()),
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+                println!("Reconnected!\n");
+                show(&network_game, renderer);
+            }
+        }
+    }
+}
+
+/// Forwards messages from the other player to the game loop, on another thread.
+fn listen(peer: &Peer, events: &Sender<Event>) -> io::Result<()> {
+    let incoming = peer.incoming()?;
+    let events = events.clone();
+
+    thread::spawn(move || {
+        for message in incoming {
+            if events.send(Event::Remote(message)).is_err() {
+                return;
+            }
+        }
+        let _ = events.send(Event::Disconnected);
+    });
+
+    Ok(())
+}
+
+/// Waits for the host to say which color we're playing, and how the game stands.
+fn wait_for_game(receiver: &Receiver<Event>) -> Option<NetworkGame> {
+    loop {
+        match receiver.recv().ok()? {
+            Event::Remote(Ok(message @ Message::Game { .. })) => {
+                match NetworkGame::from_message(&message) {
+                    Ok(network_game) => return Some(network_game),
+                    Err(e) => {
+                        println!("The host sent a game that doesn't add up: {}", e);
+                        return None;
+                    }
+                }
+            }
+            Event::Local(line) if line.trim() == "q" => return None,
+            Event::Local(_) => println!("Waiting for the host..."),
+            Event::Remote(_) => {}
+            Event::Disconnected => {
+                println!("The host hung up.");
+                return None;
+            }
+        }
+    }
+}
+
+fn reconnect(address: &str) -> io::Result<Peer> {
+    let mut attempts = 0;
+    loop {
+        thread::sleep(RECONNECT_DELAY);
+        println!("Trying to reconnect...");
+
+        match Peer::connect(with_port(address)) {
+            Ok(peer) => return Ok(peer),
+            Err(e) if attempts + 1 >= RECONNECT_ATTEMPTS => return Err(e),
+            Err(_) => attempts += 1,
+        }
+    }
+}
+
+/// Adds the default port to addresses without one.
+fn with_port(address: &str) -> String {
+    if address.contains(':') {
+        address.to_owned()
+    } else {
+        format!("{}:{}", address, DEFAULT_PORT)
+    }
+}
+
+fn local_input(line: &str, network_game: &mut NetworkGame, peer: &mut Peer, renderer: &Renderer) {
+    let line = line.trim();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    match word.to_ascii_lowercase().as_str() {
+        "say" => send(peer, Ok(Message::Chat(rest.trim().to_owned()))),
+        "resign" => resign(network_game, peer),
+        _ => match parse_input(line) {
+            Some(Input::Move(position)) => {
+                play(network_game, peer, renderer, Play::Move(position));
+            }
+            Some(Input::Pass) => play(network_game, peer, renderer, Play::Pass),
+            Some(Input::Quit) => {
+                println!("Leaving the game resigns it.");
+                resign(network_game, peer);
+            }
+            Some(Input::Transcript) => print_transcript(&network_game.game.transcript),
+            Some(Input::ValidMoves) => {
+                let game = &network_game.game;
+                let valid_moves: Vec<usize> = game.valid_moves(network_game.local).collect();
+                println!("{}", renderer.board(&game.board, &valid_moves));
+            }
+            _ => println!("What? Play a move, \"say <message>\" to chat, or \"resign\"."),
+        },
+    }
+}
+
+fn remote_message(
+    message: Message,
+    network_game: &mut NetworkGame,
+    peer: &mut Peer,
+    renderer: &Renderer,
+) {
+    match message {
+        Message::Play(play) => match network_game.play_remote(&play) {
+            Ok(()) => {
+                println!("Your opponent played {}.\n", play);
+                show(network_game, renderer);
+            }
+            Err(e) => {
+                println!("Your opponent tried to play {}, which isn't allowed.", play);
+                send(peer, Ok(Message::Error(e)));
+            }
+        },
+        Message::Chat(text) => println!("Opponent: {}", text),
+        Message::Resign => network_game.resign(network_game.local.opposite()),
+        Message::Error(e) => println!("Your opponent's game rejected that: {}", e),
+        Message::Game { .. } => println!("Ignoring an unexpected game from your opponent."),
+    }
+}
+
+fn play(network_game: &mut NetworkGame, peer: &mut Peer, renderer: &Renderer, play: Play) {
+    match network_game.play_local(&play) {
+        Ok(message) => {
+            send(peer, Ok(message));
+            show(network_game, renderer);
+        }
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn resign(network_game: &mut NetworkGame, peer: &mut Peer) {
+    send(peer, Ok(Message::Resign));
+    network_game.resign(network_game.local);
+}
+
+/// Sends a message, if there's one to send. A lost connection shows up on the reader thread,
+/// so failed sends are left to it.
+fn send(peer: &mut Peer, message: Result<Message, String>) {
+    match message {
+        Ok(message) => {
+            let _ = peer.send(&message);
+        }
+        Err(e) => println!("{}", e),
+    }
+}
+
+fn show(network_game: &NetworkGame, renderer: &Renderer) {
+    println!("{}", renderer.game(&network_game.game));
+
+    if network_game.is_local_turn() {
+        println!("Your move.");
+    } else if !network_game.is_over() {
+        println!("Waiting for your opponent...");
+    }
+}
+
+fn finish(network_game: &NetworkGame, renderer: &Renderer) {
+    let local = network_game.local;
+
+    match network_game.resigned() {
+        Some(player) if player == local => println!("You resigned."),
+        Some(_) => println!("Your opponent resigned -- you win!"),
+        None => {
+            let game = &network_game.game;
+            let (mine, theirs) = match local {
+                Disc::Dark => (game.dark, game.light),
+                Disc::Light => (game.light, game.dark),
+            };
+
+            println!("{}", renderer.game(game));
+            match mine.cmp(&theirs) {
+                std::cmp::Ordering::Greater => println!("You win, {} to {}!", mine, theirs),
+                std::cmp::Ordering::Less => println!("You lose, {} to {}.", mine, theirs),
+                std::cmp::Ordering::Equal => println!("It's a draw!"),
+            }
+        }
+    }
+
+    print_transcript(&network_game.game.transcript);
+}
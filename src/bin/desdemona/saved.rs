@@ -0,0 +1,188 @@
+use crate::players::{Player, Players};
+use desdemona::{parse_transcript, Board, Disc, Game, Play};
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+
+/// A game in progress along with who is playing each side, as written by `save` and read back by
+/// `load` or `--load`.
+///
+/// The file is a handful of `key: value` lines. Games played from the start are saved as their
+/// transcript; games set up from a position (such as puzzles) are saved as the board and the
+/// side to move, in the same format as the engine protocol:
+///
+/// ```text
+/// # desdemona saved game
+/// dark: human
+/// light: monte(rounds=500,threads=0)
+/// transcript: d3,c5,f6
+/// ```
+pub struct SavedGame {
+    pub dark: String,
+    pub light: String,
+    pub game: Game,
+}
+
+impl SavedGame {
+    pub fn new(game: &Game, players: &Players) -> Self {
+        Self {
+            dark: players.dark.spec(),
+            light: players.light.spec(),
+            game: game.clone(),
+        }
+    }
+
+    /// Reads and validates a saved game.
+    pub fn read(path: &str) -> Result<Self, String> {
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {}", path, e))?;
+        Self::parse(&text).map_err(|e| format!("Couldn't load {}: {}", path, e))
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("Couldn't save {}: {}", path, e))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut dark = None;
+        let mut light = None;
+        let mut transcript = None;
+        let mut position = None;
+        let mut turn = None;
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("expected \"key: value\", found \"{}\"", line))?;
+            let value = value.trim().to_owned();
+
+            match key.trim() {
+                "dark" => dark = Some(value),
+                "light" => light = Some(value),
+                "transcript" => transcript = Some(value),
+                "position" => position = Some(value),
+                "turn" => turn = Some(value),
+                other => return Err(format!("unknown key \"{}\"", other)),
+            }
+        }
+
+        let game = match (transcript, position) {
+            (Some(transcript), None) => game_from_transcript(&parse_transcript(&transcript)?)?,
+            (None, Some(position)) => {
+                let board = Board::from_position_string(&position)?;
+                let turn = match turn.as_deref() {
+                    Some("dark") => Disc::Dark,
+                    Some("light") => Disc::Light,
+                    Some(other) => {
+                        return Err(format!("turn should be dark or light, not {}", other))
+                    }
+                    None => return Err("a position needs a turn".to_owned()),
+                };
+                Game::from_position(board, turn)
+            }
+            (Some(_), Some(_)) => {
+                return Err("expected a transcript or a position, not both".to_owned())
+            }
+            (None, None) => return Err("missing a transcript or position".to_owned()),
+        };
+
+        Ok(Self {
+            dark: dark.ok_or("missing the dark player")?,
+            light: light.ok_or("missing the light player")?,
+            game,
+        })
+    }
+
+    /// Sets up the players the game was saved with.
+    pub fn players(&self) -> Result<Players, String> {
+        Ok(Players {
+            dark: Player::from_name(&self.dark)?,
+            light: Player::from_name(&self.light)?,
+        })
+    }
+}
+
+impl Display for SavedGame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# desdemona saved game")?;
+        writeln!(f, "dark: {}", self.dark)?;
+        writeln!(f, "light: {}", self.light)?;
+
+        // a game that was set up from a position can't be replayed from its transcript
+        match Game::from_transcript(&self.game.transcript) {
+            Some(replayed)
+                if replayed.board == self.game.board && replayed.turn == self.game.turn =>
+            {
+                let plays: Vec<String> =
+                    self.game.transcript.iter().map(|p| p.to_string()).collect();
+                writeln!(f, "transcript: {}", plays.join(","))
+            }
+            _ => {
+                writeln!(f, "position: {}", self.game.board.to_position_string())?;
+                let turn = format!("{:?}", self.game.turn).to_lowercase();
+                writeln!(f, "turn: {}", turn)
+            }
+        }
+    }
+}
+
+/// Replays a transcript with [Game::from_transcript], pointing out the first illegal play if
+/// there is one.
+fn game_from_transcript(plays: &[Play]) -> Result<Game, String> {
+    Game::from_transcript(plays).ok_or_else(|| {
+        let mut game = Game::new();
+        for (n, play) in plays.iter().enumerate() {
+            match play {
+                Play::Pass => game.pass(),
+                Play::Move(position) => match game.validate_move(game.turn, position.into()) {
+                    Some(valid_move) => game.play(valid_move),
+                    None => {
+                        return format!("play {} of the transcript ({}) is illegal", n + 1, play)
+                    }
+                },
+            }
+        }
+        "the transcript isn't a legal game".to_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let text =
+            "# desdemona saved game\ndark: human\nlight: monte(rounds=5)\ntranscript: d3,c5\n";
+        let saved = SavedGame::parse(text).unwrap();
+
+        assert_eq!(saved.game.transcript.len(), 2);
+        assert_eq!(saved.to_string(), text);
+
+        let moved = Game::from_position(saved.game.board, Disc::Light);
+        let saved = SavedGame {
+            game: moved,
+            ..saved
+        };
+        let reparsed = SavedGame::parse(&saved.to_string()).unwrap();
+        assert_eq!(reparsed.game.board, saved.game.board);
+        assert_eq!(reparsed.game.turn, Disc::Light);
+    }
+
+    #[test]
+    fn test_errors() {
+        let illegal = SavedGame::parse("dark: human\nlight: human\ntranscript: d3,a1");
+        assert_eq!(
+            illegal.err(),
+            Some("play 2 of the transcript (a1) is illegal".to_owned())
+        );
+
+        assert!(SavedGame::parse("dark: human\ntranscript: d3").is_err());
+        assert!(SavedGame::parse("dark: human\nlight: human").is_err());
+        assert!(SavedGame::parse("nonsense").is_err());
+    }
+}
@@ -0,0 +1,522 @@
+use crate::players::{pondering, Player, Players};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEventKind,
+};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, queue};
+use desdemona::render::Theme;
+use desdemona::strategies::{Search, Strategy};
+use desdemona::{Disc, Game, Play, Position};
+use std::io::{self, Stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// screen layout
+const BOARD_TOP: u16 = 2;
+const BOARD_LEFT: u16 = 2;
+const CELL_WIDTH: u16 = 3;
+const MOVES_LEFT: u16 = 32;
+const MOVE_ROWS: usize = 14;
+const SCORE_BAR_WIDTH: usize = 24;
+const STATUS_TOP: u16 = BOARD_TOP + 12;
+
+/// How the session ended.
+pub enum Outcome {
+    Complete,
+    Quit,
+}
+
+/// Puts the terminal into full screen mode, and restores it when dropped (even on panic).
+struct Screen {
+    stdout: Stdout,
+}
+
+impl Screen {
+    fn new() -> io::Result<Self> {
+        let mut stdout = io::stdout();
+        terminal::enable_raw_mode()?;
+        queue!(
+            stdout,
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            cursor::Hide
+        )?;
+        stdout.flush()?;
+        Ok(Self { stdout })
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = queue!(
+            self.stdout,
+            cursor::Show,
+            DisableMouseCapture,
+            LeaveAlternateScreen
+        );
+        let _ = self.stdout.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Everything on screen besides the game itself.
+struct View {
+    cursor: (usize, usize),
+    last_move: Option<usize>,
+    flipped: Vec<usize>,
+    status: String,
+    theme: Theme,
+}
+
+/// Plays the game in a full screen terminal UI, with cursor (or mouse) driven move entry.
+pub fn run(
+    game: &mut Game,
+    players: &mut Players,
+    hint_strategy: &mut dyn Strategy,
+    delay: Duration,
+    movetime: Option<Duration>,
+    ponder: bool,
+    theme: Theme,
+) -> io::Result<Outcome> {
+    let mut screen = Screen::new()?;
+    let mut view = View {
+        cursor: (3, 2),
+        last_move: None,
+        flipped: vec![],
+        status: String::new(),
+        theme,
+    };
+
+    loop {
+        if game.is_complete {
+            view.status = format!("{} Press any key to exit.", result(game));
+            draw(&mut screen.stdout, game, players, &view)?;
+            wait_for_key()?;
+            return Ok(Outcome::Complete);
+        }
+
+        match players.get_mut(game.turn) {
+            Player::Computer(strategy, _) => {
+                view.status = format!("{} ({:?}) is thinking...", strategy.name(), game.turn);
+                draw(&mut screen.stdout, game, players, &view)?;
+
+                // keep the pause responsive, so quitting doesn't have to wait for it
+                if quit_requested(delay)? {
+                    return Ok(Outcome::Quit);
+                }
+
+                let Player::Computer(strategy, _) = players.get_mut(game.turn) else {
+                    unreachable!()
+                };
+                match think(strategy.as_mut(), game, &view.status, movetime)? {
+                    Some(play) => apply(game, &mut view, play),
+                    None => return Ok(Outcome::Quit),
+                }
+            }
+            Player::Human => {
+                // the computer, if it's playing, ponders for the whole of the human's turn
+                let outcome = if ponder {
+                    let position = game.clone();
+                    pondering(players.get(game.turn.opposite()), &position, || {
+                        human_turn(&mut screen.stdout, game, players, &mut view, hint_strategy)
+                    })?
+                } else {
+                    human_turn(&mut screen.stdout, game, players, &mut view, hint_strategy)?
+                };
+                if let Some(Outcome::Quit) = outcome {
+                    return Ok(Outcome::Quit);
+                }
+            }
+        }
+    }
+}
+
+/// Asks the computer for its play, showing its progress after `status`, and stopping it after
+/// `movetime` if there is one. Quitting stops it too, and returns `None`.
+fn think(
+    strategy: &mut dyn Strategy,
+    game: &Game,
+    status: &str,
+    movetime: Option<Duration>,
+) -> io::Result<Option<Option<usize>>> {
+    let search = Search::new().with_info(|info| {
+        let best = match info.best {
+            Some(index) => Position::new(index).to_string(),
+            None => "pass".to_owned(),
+        };
+        let mut out = io::stdout();
+        let _ = queue!(
+            out,
+            cursor::MoveTo(BOARD_LEFT, STATUS_TOP),
+            Clear(ClearType::UntilNewLine),
+            Print(format!(
+                "{} depth {}, {} positions, best {}",
+                status, info.depth, info.nodes, best
+            ))
+        );
+        let _ = out.flush();
+    });
+
+    // watch for quitting on another thread, since the search has this one
+    let done = AtomicBool::new(false);
+    let quit = AtomicBool::new(false);
+    let play = thread::scope(|scope| {
+        let watcher = scope.spawn(|| -> io::Result<()> {
+            while !done.load(Ordering::Relaxed) {
+                if quit_requested(Duration::from_millis(50))? {
+                    quit.store(true, Ordering::Relaxed);
+                    search.stop();
+                    break;
+                }
+            }
+            Ok(())
+        });
+
+        let play = match movetime {
+            Some(limit) => search.stop_after(limit, || strategy.search_play(game, &search)),
+            None => strategy.search_play(game, &search),
+        };
+        done.store(true, Ordering::Relaxed);
+
+        watcher
+            .join()
+            .expect("the quit watcher panicked")
+            .map(|_| play)
+    })?;
+
+    Ok((!quit.load(Ordering::Relaxed)).then_some(play))
+}
+
+/// Handles a human player's input events until they play, pass, or quit.
+fn human_turn(
+    out: &mut Stdout,
+    game: &mut Game,
+    players: &Players,
+    view: &mut View,
+    hint_strategy: &mut dyn Strategy,
+) -> io::Result<Option<Outcome>> {
+    let played = game.transcript.len();
+
+    while game.transcript.len() == played && !game.is_complete {
+        draw(out, game, players, view)?;
+        if let Some(outcome) = human_input(game, view, hint_strategy)? {
+            return Ok(Some(outcome));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Handles a single input event from a human player.
+fn human_input(
+    game: &mut Game,
+    view: &mut View,
+    hint_strategy: &mut dyn Strategy,
+) -> io::Result<Option<Outcome>> {
+    let (x, y) = view.cursor;
+
+    match event::read()? {
+        Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) => match code {
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Some(Outcome::Quit))
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(Some(Outcome::Quit)),
+            KeyCode::Left => view.cursor = (x.saturating_sub(1), y),
+            KeyCode::Right => view.cursor = ((x + 1).min(7), y),
+            KeyCode::Up => view.cursor = (x, y.saturating_sub(1)),
+            KeyCode::Down => view.cursor = (x, (y + 1).min(7)),
+            KeyCode::Enter | KeyCode::Char(' ') => try_move(game, view),
+            KeyCode::Char('p') => {
+                if game.valid_moves(game.turn).next().is_some() {
+                    view.status = "You can't pass while you have valid moves.".to_owned();
+                } else {
+                    apply(game, view, None);
+                }
+            }
+            KeyCode::Char('h') => view.status = hints(game, hint_strategy),
+            _ => {}
+        },
+        Event::Mouse(mouse) if mouse.kind == MouseEventKind::Down(MouseButton::Left) => {
+            if let Some(cell) = cell_at(mouse.column, mouse.row) {
+                view.cursor = cell;
+                try_move(game, view);
+            }
+        }
+        _ => {}
+    }
+
+    Ok(None)
+}
+
+/// Plays at the cursor, if it's a valid move.
+fn try_move(game: &mut Game, view: &mut View) {
+    let (x, y) = view.cursor;
+    let index = Position::from_xy(x, y).into();
+
+    match game.validate_move(game.turn, index) {
+        Some(index) => apply(game, view, Some(index)),
+        None => view.status = format!("{} isn't a valid move.", Position::new(index)),
+    }
+}
+
+/// Plays (or passes), remembering what changed so it can be highlighted.
+fn apply(game: &mut Game, view: &mut View, play: Option<usize>) {
+    match play {
+        Some(index) => {
+            view.flipped = game.flips_for(index);
+            view.last_move = Some(index);
+            view.status = format!("{:?} played {}.", game.turn, Position::new(index));
+            game.play(index);
+        }
+        None => {
+            view.flipped.clear();
+            view.last_move = None;
+            view.status = format!("{:?} passed.", game.turn);
+            game.pass();
+        }
+    }
+}
+
+fn hints(game: &Game, strategy: &mut dyn Strategy) -> String {
+    let mut scored_plays = strategy.score_plays(game);
+    scored_plays.sort_by(|a, b| b.cmp(a));
+
+    let best: Vec<String> = scored_plays
+        .iter()
+        .take(3)
+        .map(|s| format!("{} ({:.2})", Position::new(s.index), s.score))
+        .collect();
+
+    if best.is_empty() {
+        "No valid moves; press p to pass.".to_owned()
+    } else {
+        format!("Hints from {}: {}", strategy.name(), best.join(", "))
+    }
+}
+
+fn result(game: &Game) -> String {
+    if game.dark > game.light {
+        format!("Dark wins, {} to {}!", game.dark, game.light)
+    } else if game.light > game.dark {
+        format!("Light wins, {} to {}!", game.light, game.dark)
+    } else {
+        format!("It's a draw, {} all!", game.dark)
+    }
+}
+
+/// Translates screen coordinates into a board cell.
+fn cell_at(column: u16, row: u16) -> Option<(usize, usize)> {
+    let left = BOARD_LEFT + 2;
+    let top = BOARD_TOP + 1;
+    if column < left || row < top {
+        return None;
+    }
+
+    let x = ((column - left) / CELL_WIDTH) as usize;
+    let y = (row - top) as usize;
+    if x > 7 || y > 7 {
+        return None;
+    }
+
+    Some((x, y))
+}
+
+/// Waits for `delay`, returning early (with `true`) if the user asks to quit.
+fn quit_requested(delay: Duration) -> io::Result<bool> {
+    let deadline = Instant::now() + delay;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if !event::poll(remaining)? {
+            return Ok(false);
+        }
+
+        if let Event::Key(KeyEvent {
+            code: KeyCode::Char('q') | KeyCode::Esc,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            return Ok(true);
+        }
+    }
+}
+
+fn wait_for_key() -> io::Result<()> {
+    loop {
+        if let Event::Key(KeyEvent {
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            return Ok(());
+        }
+    }
+}
+
+fn draw(out: &mut Stdout, game: &Game, players: &Players, view: &View) -> io::Result<()> {
+    queue!(out, Clear(ClearType::All), cursor::MoveTo(BOARD_LEFT, 0))?;
+    queue!(
+        out,
+        Print(format!(
+            "Desdemona: {} (dark) vs {} (light)",
+            players.dark.name(),
+            players.light.name()
+        ))
+    )?;
+
+    draw_board(out, game, players, view)?;
+    draw_moves(out, game)?;
+    draw_score(out, game, view.theme)?;
+
+    let bottom = STATUS_TOP;
+    queue!(out, cursor::MoveTo(BOARD_LEFT, bottom), Print(&view.status))?;
+
+    if !game.is_complete && game.valid_moves(game.turn).next().is_none() {
+        queue!(
+            out,
+            cursor::MoveTo(BOARD_LEFT, bottom + 1),
+            Print(format!(
+                "{:?} has no valid moves, and must pass.",
+                game.turn
+            ))
+        )?;
+    }
+
+    queue!(
+        out,
+        cursor::MoveTo(BOARD_LEFT, bottom + 2),
+        SetForegroundColor(Color::DarkGrey),
+        Print("arrows/mouse: select · enter/click: play · p: pass · h: hint · q: quit"),
+        ResetColor
+    )?;
+
+    out.flush()
+}
+
+fn draw_board(out: &mut Stdout, game: &Game, players: &Players, view: &View) -> io::Result<()> {
+    let human_turn = match game.turn {
+        Disc::Dark => players.dark.is_human(),
+        Disc::Light => players.light.is_human(),
+    };
+    let valid_moves: Vec<usize> = if human_turn {
+        game.valid_moves(game.turn).collect()
+    } else {
+        vec![]
+    };
+
+    queue!(
+        out,
+        cursor::MoveTo(BOARD_LEFT, BOARD_TOP),
+        Print("   a  b  c  d  e  f  g  h")
+    )?;
+
+    for y in 0..8 {
+        queue!(
+            out,
+            cursor::MoveTo(BOARD_LEFT, BOARD_TOP + 1 + y as u16),
+            Print(format!("{} ", y + 1))
+        )?;
+
+        for x in 0..8 {
+            let index: usize = Position::from_xy(x, y).into();
+
+            let background = if human_turn && view.cursor == (x, y) {
+                Color::DarkBlue
+            } else if view.last_move == Some(index) {
+                Color::DarkYellow
+            } else if view.flipped.contains(&index) {
+                Color::DarkMagenta
+            } else {
+                Color::DarkGreen
+            };
+
+            // the board is already colored, so only the theme's symbols (and disc colors) are used
+            let disc = game.board.get(index);
+            let symbol = match disc {
+                None if valid_moves.contains(&index) => "+",
+                _ => view.theme.symbol(disc),
+            };
+            let foreground = match (disc, view.theme.palette) {
+                (Some(Disc::Dark), Some(_)) => Color::Black,
+                (Some(Disc::Light), Some(_)) => Color::White,
+                _ => Color::Reset,
+            };
+
+            queue!(
+                out,
+                SetBackgroundColor(background),
+                SetForegroundColor(foreground),
+                Print(format!(" {} ", symbol)),
+                ResetColor
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn draw_moves(out: &mut Stdout, game: &Game) -> io::Result<()> {
+    queue!(out, cursor::MoveTo(MOVES_LEFT, BOARD_TOP), Print("Moves"))?;
+
+    let rows: Vec<String> = game
+        .transcript
+        .chunks(2)
+        .enumerate()
+        .map(|(n, plays)| {
+            let plays: Vec<String> = plays.iter().map(format_play).collect();
+            format!("{:>2}. {}", n + 1, plays.join("  "))
+        })
+        .collect();
+
+    // keep the most recent plays in view
+    let skip = rows.len().saturating_sub(MOVE_ROWS);
+    for (line, row) in rows.iter().skip(skip).enumerate() {
+        queue!(
+            out,
+            cursor::MoveTo(MOVES_LEFT, BOARD_TOP + 1 + line as u16),
+            Print(row)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn format_play(play: &Play) -> String {
+    match play {
+        Play::Move(position) => format!("{}", position),
+        Play::Pass => "--".to_owned(),
+    }
+}
+
+fn draw_score(out: &mut Stdout, game: &Game, theme: Theme) -> io::Result<()> {
+    let total = (game.dark + game.light).max(1);
+    let dark_width = game.dark * SCORE_BAR_WIDTH / total;
+
+    queue!(
+        out,
+        cursor::MoveTo(BOARD_LEFT, BOARD_TOP + 10),
+        Print(format!("{} {:>2} ", theme.dark, game.dark)),
+        SetBackgroundColor(Color::Black),
+        Print(" ".repeat(dark_width)),
+        SetBackgroundColor(Color::White),
+        Print(" ".repeat(SCORE_BAR_WIDTH - dark_width)),
+        ResetColor,
+        Print(format!(" {:<2} {}", game.light, theme.light))
+    )?;
+
+    if game.is_complete {
+        queue!(out, Print("   Game over"))?;
+    } else {
+        queue!(out, Print(format!("   {:?} to play", game.turn)))?;
+    }
+
+    Ok(())
+}
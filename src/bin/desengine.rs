@@ -0,0 +1,105 @@
+use clap::{App, ArgMatches};
+use desdemona::nboard::NBoardSession;
+use desdemona::protocol::{Command, Response, Session};
+use desdemona::strategies::*;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::net::TcpStream;
+use std::sync::Mutex;
+
+fn main() -> Result<(), io::Error> {
+    let config = get_args();
+
+    let strategy = match from_name(config.value_of("strategy").unwrap_or("minimize")) {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            let error = format!("{} -- available strategies are:\n{}", e, registry().help());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+        }
+    };
+
+    if let Some(address) = config.value_of("connect") {
+        // introduce ourselves to the server, then play whatever games it sends
+        let spec = config.value_of("strategy").unwrap_or("minimize");
+        let mut stream = TcpStream::connect(address)?;
+        // info lines go out on their own, so don't hold them back waiting for more
+        stream.set_nodelay(true)?;
+        writeln!(stream, "engine {}", spec)?;
+        let reader = BufReader::new(stream.try_clone()?);
+        return serve(Session::new(strategy), reader, BufWriter::new(stream));
+    }
+
+    if config.is_present("nboard") {
+        serve_nboard(NBoardSession::new(strategy))
+    } else {
+        serve(Session::new(strategy), io::stdin().lock(), io::stdout())
+    }
+}
+
+fn serve(mut session: Session, input: impl BufRead, output: impl Write + Send) -> io::Result<()> {
+    // info lines are written as the search goes, from whichever thread is searching
+    let output = Mutex::new(output);
+    let send = |responses: &[Response]| -> io::Result<()> {
+        let mut output = output.lock().unwrap();
+        for response in responses {
+            writeln!(output, "{}", response)?;
+        }
+        output.flush()
+    };
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let responses = match line.parse::<Command>() {
+            Ok(Command::Quit) => break,
+            Ok(command) => session.handle_streaming(command, |info| {
+                // a broken connection shows up again when the move is sent
+                let _ = send(&[info]);
+            }),
+            Err(e) => vec![Response::Error(e)],
+        };
+
+        send(&responses)?;
+    }
+
+    Ok(())
+}
+
+fn serve_nboard(mut session: NBoardSession) -> Result<(), io::Error> {
+    let send = |output: String| -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        writeln!(stdout, "{}", output)?;
+        stdout.flush()
+    };
+
+    for line in io::stdin().lock().lines() {
+        let outputs = session.handle_streaming(&line?, |output| {
+            let _ = send(output);
+        });
+        for output in outputs {
+            send(output)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn get_args() -> ArgMatches<'static> {
+    let about = format!(
+        "Plays any strategy as an engine over stdin/stdout, using Desdemona's engine protocol (or NBoard's, with --nboard). With --connect, it joins a desserver's queue instead, and plays the games it's given. Available strategies (and their parameters) are:\n{}\nParameters are given in parentheses, eg: monte(rounds=2000,threads=4)",
+        registry().help()
+    );
+
+    App::new("desengine")
+        .version("0.1")
+        .author("Peat Bakke <peat@peat.org>")
+        .long_about(about.as_str())
+        .args_from_usage(
+            "-s, --strategy=[STRATEGY]       'The strategy to play (default minimize)'
+            --nboard                        'Speak the NBoard protocol instead'
+            --connect=[ADDRESS]             'Play games hosted by desserver at ADDRESS (eg: localhost:7475)'",
+        )
+        .get_matches()
+}
@@ -0,0 +1,587 @@
+use desdemona::strategies::{from_config, Config, ExternalEngine, ScoredPlay, Search, Strategy};
+use desdemona::{parse_transcript, Board, Disc, Game, Play, Position};
+use serde_json::{json, Map, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The time a strategy gets for `/bestmove` and `/analyze` when none is given, and the most it
+/// can ask for.
+const DEFAULT_MOVETIME: u64 = 1000;
+const MAX_MOVETIME: u64 = 60_000;
+
+/// How long past `movetime` a strategy has to answer once it's been stopped.
+const STOP_GRACE: Duration = Duration::from_millis(500);
+
+/// The most strategies running at once. Ones that can't be stopped keep their place until
+/// they finish, even after their request has given up on them.
+const MAX_SEARCHES: usize = 8;
+
+static SEARCHES: AtomicUsize = AtomicUsize::new(0);
+
+/// The most a request can ask for of the strategy parameters that cost memory or threads, which
+/// `movetime` does nothing to limit: megabytes of table, threads, and Monte's games per move.
+const LIMITS: [(&str, u64); 3] = [("table", 64), ("threads", 4), ("rounds", 10_000)];
+
+/// The strategies used by `/bestmove` and `/analyze` when none is given.
+const DEFAULT_STRATEGY: &str = "minimize";
+const DEFAULT_ANALYSIS: &str = "alphabeta";
+
+/// The JSON schemas for requests and responses, served at `GET /schema`.
+const SCHEMA: &str = include_str!("schema.json");
+
+/// An HTTP status and JSON body.
+pub type Reply = (u16, Value);
+
+/// Refuses requests that a web page could have sent from the user's browser: a POST has to say
+/// it's `application/json`, which a page can't send to another site without permission, and
+/// anything sent from a page that isn't on this machine is turned away.
+pub fn check_headers(
+    method: &str,
+    content_type: Option<&str>,
+    origin: Option<&str>,
+) -> Result<(), Reply> {
+    if let Some(origin) = origin {
+        if !is_local_origin(origin) {
+            let error = format!("Requests from {} aren't allowed", origin);
+            return Err((403, json!({ "error": error })));
+        }
+    }
+
+    let is_json = content_type
+        .and_then(|value| value.split(';').next())
+        .is_some_and(|media| media.trim().eq_ignore_ascii_case("application/json"));
+    if method == "POST" && !is_json {
+        let error = "Requests need a \"Content-Type: application/json\" header";
+        return Err((415, json!({ "error": error })));
+    }
+
+    Ok(())
+}
+
+/// Whether an `Origin` header is a page served from this machine, eg: `http://localhost:8000`.
+fn is_local_origin(origin: &str) -> bool {
+    let host = match origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+    {
+        Some(host) => host,
+        None => return false,
+    };
+
+    // without the port, if there is one
+    let host = match host.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => host,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Answers a request. Every endpoint but `/schema` takes a JSON object describing a game (see
+/// [game_from_json]), along with anything else the endpoint needs.
+pub fn handle(method: &str, path: &str, body: &str) -> Reply {
+    let result = match (method, path) {
+        ("GET", "/schema") => serde_json::from_str(SCHEMA).map_err(|e| (500, e.to_string())),
+        ("POST", "/moves") => parse(body).and_then(|request| moves(&request)),
+        ("POST", "/play") => parse(body).and_then(|request| play(&request)),
+        ("POST", "/bestmove") => parse(body).and_then(|request| best_move(&request)),
+        ("POST", "/analyze") => parse(body).and_then(|request| analyze(&request)),
+        (_, "/schema" | "/moves" | "/play" | "/bestmove" | "/analyze") => {
+            Err((405, format!("{} isn't supported for {}", method, path)))
+        }
+        _ => Err((404, format!("No such endpoint: {}", path))),
+    };
+
+    match result {
+        Ok(value) => (200, value),
+        Err((status, error)) => (status, json!({ "error": error })),
+    }
+}
+
+type Request = Map<String, Value>;
+type Response = Result<Value, (u16, String)>;
+
+fn parse(body: &str) -> Result<Request, (u16, String)> {
+    if body.trim().is_empty() {
+        return Ok(Map::new());
+    }
+
+    match serde_json::from_str(body) {
+        Ok(Value::Object(request)) => Ok(request),
+        Ok(_) => Err(bad_request("The request should be a JSON object")),
+        Err(e) => Err(bad_request(format!("Invalid JSON: {}", e))),
+    }
+}
+
+fn bad_request(error: impl Into<String>) -> (u16, String) {
+    (400, error.into())
+}
+
+/// `POST /moves`: the valid moves for the player to move.
+fn moves(request: &Request) -> Response {
+    let game = game_from_json(request).map_err(bad_request)?;
+    let moves: Vec<Value> = game
+        .valid_moves(game.turn)
+        .map(|index| play_to_json(&Play::Move(Position::new(index))))
+        .collect();
+
+    Ok(json!({ "turn": disc_to_json(game.turn), "moves": moves }))
+}
+
+/// `POST /play`: the game after a play.
+fn play(request: &Request) -> Response {
+    let mut game = game_from_json(request).map_err(bad_request)?;
+    let play = string_field(request, "play")?
+        .ok_or_else(|| bad_request("Missing \"play\""))?
+        .parse::<Play>()
+        .map_err(bad_request)?;
+
+    if game.is_complete {
+        return Err(bad_request("The game is already over"));
+    }
+    game.apply(&play).map_err(bad_request)?;
+
+    Ok(game_to_json(&game))
+}
+
+/// `POST /bestmove`: asks a strategy for its play, stopping its search after `movetime`
+/// milliseconds.
+fn best_move(request: &Request) -> Response {
+    let game = game_from_json(request).map_err(bad_request)?;
+    check_not_over(&game)?;
+
+    let config = strategy_config(request, DEFAULT_STRATEGY)?;
+    let spec = config.to_string();
+    let started = Instant::now();
+
+    // with nothing to choose from, there's no need to ask
+    if game.valid_moves(game.turn).next().is_none() {
+        return Ok(json!({
+            "play": play_to_json(&Play::Pass),
+            "strategy": spec,
+            "elapsed": started.elapsed().as_millis() as u64,
+        }));
+    }
+
+    let choice = run(config, movetime(request)?, move |strategy, search| {
+        strategy.search_play(&game, search)
+    })?;
+    let play = match choice {
+        Some(index) => Play::Move(Position::new(index)),
+        None => Play::Pass,
+    };
+
+    Ok(json!({
+        "play": play_to_json(&play),
+        "strategy": spec,
+        "elapsed": started.elapsed().as_millis() as u64,
+    }))
+}
+
+/// `POST /analyze`: every valid move, scored by a strategy, best first. The search is stopped
+/// after `movetime` milliseconds, and a strategy that gets stopped may not have scored them
+/// all.
+fn analyze(request: &Request) -> Response {
+    let game = game_from_json(request).map_err(bad_request)?;
+    check_not_over(&game)?;
+
+    let config = strategy_config(request, DEFAULT_ANALYSIS)?;
+    let spec = config.to_string();
+
+    if game.valid_moves(game.turn).next().is_none() {
+        return Ok(json!({ "strategy": spec, "plays": [] }));
+    }
+
+    let mut scored = run(config, movetime(request)?, move |strategy, search| {
+        strategy.search_scores(&game, search)
+    })?;
+    scored.sort_by(|a, b| b.cmp(a));
+
+    Ok(json!({
+        "strategy": spec,
+        "plays": scored.iter().map(scored_play_to_json).collect::<Vec<Value>>(),
+    }))
+}
+
+/// Refuses a game that's over, whether it ended in play or was set up that way.
+fn check_not_over(game: &Game) -> Result<(), (u16, String)> {
+    if game.is_complete || game.state().is_over() {
+        return Err(bad_request("The game is already over"));
+    }
+
+    Ok(())
+}
+
+/// The `strategy` a request asks for. External engines are refused, since they'd let any
+/// request start any program, and so is anything over the [LIMITS].
+fn strategy_config(request: &Request, default: &str) -> Result<Config, (u16, String)> {
+    let spec = string_field(request, "strategy")?.unwrap_or(default);
+    let config = Config::parse(spec).map_err(|e| bad_request(e.to_string()))?;
+
+    if config
+        .name
+        .eq_ignore_ascii_case(ExternalEngine::REGISTRATION.name)
+    {
+        return Err((
+            403,
+            "External engines aren't available over HTTP".to_owned(),
+        ));
+    }
+
+    // a value that isn't a number is left for the strategy to complain about
+    for (name, limit) in LIMITS {
+        let value = config
+            .value(name)
+            .and_then(|value| value.parse::<u64>().ok());
+        if value.is_some_and(|value| value > limit) {
+            return Err(bad_request(format!(
+                "\"{}\" can be at most {} over HTTP",
+                name, limit
+            )));
+        }
+    }
+
+    Ok(config)
+}
+
+/// The request's `movetime`, or the default.
+fn movetime(request: &Request) -> Result<Duration, (u16, String)> {
+    let movetime = match request.get("movetime") {
+        None => DEFAULT_MOVETIME,
+        Some(value) => value
+            .as_u64()
+            .filter(|&ms| ms <= MAX_MOVETIME)
+            .ok_or_else(|| {
+                bad_request(format!(
+                    "\"movetime\" should be up to {} milliseconds",
+                    MAX_MOVETIME
+                ))
+            })?,
+    };
+
+    Ok(Duration::from_millis(movetime))
+}
+
+/// Builds the strategy and runs `f` with it on its own thread, stopping the search after
+/// `limit`. Not every strategy can be stopped, so one that still hasn't answered shortly after
+/// is left to finish on its own, holding its place in [MAX_SEARCHES] until it does.
+fn run<T: Send + 'static>(
+    config: Config,
+    limit: Duration,
+    f: impl FnOnce(&mut dyn Strategy, &Search) -> T + Send + 'static,
+) -> Result<T, (u16, String)> {
+    let running = SEARCHES.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |running| {
+        (running < MAX_SEARCHES).then_some(running + 1)
+    });
+    if running.is_err() {
+        return Err((
+            503,
+            "Too many searches are running; try again later".to_owned(),
+        ));
+    }
+    let slot = Slot;
+
+    // strategies aren't Send, so it's made on the thread that runs it
+    let (sender, receiver) = mpsc::channel();
+    let spec = config.to_string();
+    thread::spawn(move || {
+        let result = from_config(&config)
+            .map(|mut strategy| {
+                let search = Search::new();
+                search.stop_after(limit, || f(strategy.as_mut(), &search))
+            })
+            .map_err(|e| e.to_string());
+        drop(slot);
+        let _ = sender.send(result);
+    });
+
+    match receiver.recv_timeout(limit + STOP_GRACE) {
+        Ok(result) => result.map_err(bad_request),
+        Err(RecvTimeoutError::Timeout) => Err((
+            504,
+            format!("{} didn't finish within {}ms", spec, limit.as_millis()),
+        )),
+        Err(RecvTimeoutError::Disconnected) => {
+            Err((500, format!("{} failed without answering", spec)))
+        }
+    }
+}
+
+/// A place in [MAX_SEARCHES], given back when it's dropped, so a strategy that panics doesn't
+/// keep it.
+struct Slot;
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        SEARCHES.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn string_field<'a>(request: &'a Request, name: &str) -> Result<Option<&'a str>, (u16, String)> {
+    match request.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(bad_request(format!("\"{}\" should be a string", name))),
+    }
+}
+
+/// Sets up a game from a request: either a `transcript` played from the opening position, or
+/// a `board` and `turn`. If both are given, the transcript has to arrive at the board. With
+/// neither, it's the opening position.
+pub fn game_from_json(request: &Request) -> Result<Game, String> {
+    let transcript = match request.get("transcript") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(transcript)) => Some(parse_transcript(transcript)?),
+        Some(Value::Array(plays)) => Some(
+            plays
+                .iter()
+                .map(|play| match play {
+                    Value::String(play) => play.parse(),
+                    other => Err(format!("Invalid play: {}", other)),
+                })
+                .collect::<Result<Vec<Play>, String>>()?,
+        ),
+        Some(other) => return Err(format!("Invalid transcript: {}", other)),
+    };
+
+    let position = match (request.get("board"), request.get("turn")) {
+        (None, None) => None,
+        (Some(Value::String(board)), Some(Value::String(turn))) => {
+            Some((Board::from_position_string(board)?, disc_from_json(turn)?))
+        }
+        (Some(_), None) => return Err("A board needs a \"turn\" to go with it".to_owned()),
+        (None, Some(_)) => return Err("A turn needs a \"board\" to go with it".to_owned()),
+        _ => return Err("\"board\" and \"turn\" should be strings".to_owned()),
+    };
+
+    match (transcript, position) {
+        (Some(transcript), position) => {
+            // replayed play by play first, to say which play is the problem
+            let mut replay = Game::new();
+            for (n, play) in transcript.iter().enumerate() {
+                replay
+                    .apply(play)
+                    .map_err(|e| format!("Play {} of the transcript: {}", n + 1, e))?;
+            }
+
+            let game = Game::from_transcript(&transcript)
+                .ok_or_else(|| "The transcript has an invalid play".to_owned())?;
+            match position {
+                Some((board, turn)) if board != game.board || turn != game.turn => {
+                    Err("The transcript doesn't lead to the board".to_owned())
+                }
+                _ => Ok(game),
+            }
+        }
+        (None, Some((board, turn))) => Ok(Game::from_position(board, turn)),
+        (None, None) => Ok(Game::new()),
+    }
+}
+
+pub fn game_to_json(game: &Game) -> Value {
+    json!({
+        "board": game.board.to_position_string(),
+        "turn": disc_to_json(game.turn),
+        "dark": game.dark,
+        "light": game.light,
+        "empty": game.empty,
+        "transcript": game.transcript.iter().map(play_to_json).collect::<Vec<Value>>(),
+        "complete": game.is_complete,
+    })
+}
+
+pub fn play_to_json(play: &Play) -> Value {
+    Value::String(play.to_string())
+}
+
+pub fn scored_play_to_json(scored: &ScoredPlay) -> Value {
+    json!({
+        "play": play_to_json(&Play::Move(Position::new(scored.index))),
+        // by way of the f32's shortest form, so 0.8 doesn't come out as 0.800000011920929
+        "score": scored.score.to_string().parse::<f64>().unwrap_or_default(),
+        "strategy": scored.strategy.name(),
+    })
+}
+
+fn disc_to_json(disc: Disc) -> Value {
+    Value::String(format!("{:?}", disc).to_lowercase())
+}
+
+fn disc_from_json(turn: &str) -> Result<Disc, String> {
+    match turn {
+        "dark" => Ok(Disc::Dark),
+        "light" => Ok(Disc::Light),
+        _ => Err(format!("Invalid turn: {} (expected dark or light)", turn)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn post(path: &str, body: Value) -> Reply {
+        handle("POST", path, &body.to_string())
+    }
+
+    #[test]
+    fn test_moves_and_play() {
+        let (status, reply) = post("/moves", json!({}));
+        assert_eq!(status, 200);
+        assert_eq!(reply["moves"], json!(["d3", "c4", "f5", "e6"]));
+
+        let (status, reply) = post("/play", json!({ "transcript": "d3", "play": "c5" }));
+        assert_eq!(status, 200);
+        assert_eq!(reply["transcript"], json!(["d3", "c5"]));
+        assert_eq!(reply["turn"], "dark");
+
+        // a game from /play can be sent straight back
+        let mut next = reply.as_object().unwrap().clone();
+        next.insert("play".to_owned(), json!("e6"));
+        let (status, reply) = post("/play", Value::Object(next));
+        assert_eq!(status, 200);
+        assert_eq!(reply["empty"], 57);
+
+        let (status, reply) = post("/play", json!({ "play": "a1" }));
+        assert_eq!(status, 400);
+        assert_eq!(reply["error"], "Invalid move: a1");
+    }
+
+    #[test]
+    fn test_positions() {
+        let board = Game::from_transcript(&parse_transcript("d3").unwrap())
+            .unwrap()
+            .board
+            .to_position_string();
+
+        let (status, reply) = post("/moves", json!({ "board": board, "turn": "light" }));
+        assert_eq!(status, 200);
+        assert_eq!(reply["moves"].as_array().unwrap().len(), 3);
+
+        let (status, _) = post(
+            "/moves",
+            json!({ "board": board, "turn": "dark" , "transcript": ["d3"] }),
+        );
+        assert_eq!(status, 400);
+        let (status, _) = post("/moves", json!({ "board": "XO", "turn": "light" }));
+        assert_eq!(status, 400);
+        let (status, _) = post("/moves", json!({ "board": board }));
+        assert_eq!(status, 400);
+    }
+
+    #[test]
+    fn test_bestmove_and_analyze() {
+        let (status, reply) = post(
+            "/bestmove",
+            json!({ "transcript": "d3,c5", "strategy": "maximize", "movetime": 5000 }),
+        );
+        assert_eq!(status, 200);
+        let play: Play = reply["play"].as_str().unwrap().parse().unwrap();
+        let game = Game::from_transcript(&parse_transcript("d3,c5").unwrap()).unwrap();
+        assert!(game.clone().apply(&play).is_ok());
+
+        // a search that would take far too long is stopped, and still plays
+        let (status, reply) = post(
+            "/bestmove",
+            json!({ "transcript": "d3,c5", "strategy": "alphabeta(depth=30)", "movetime": 100 }),
+        );
+        assert_eq!(status, 200);
+        let play: Play = reply["play"].as_str().unwrap().parse().unwrap();
+        assert!(game.clone().apply(&play).is_ok());
+
+        let (status, _) = post("/bestmove", json!({ "strategy": "nonsense" }));
+        assert_eq!(status, 400);
+        let (status, _) = post(
+            "/bestmove",
+            json!({ "strategy": "external(command=/bin/sh)" }),
+        );
+        assert_eq!(status, 403);
+
+        let (status, reply) = post(
+            "/analyze",
+            json!({ "transcript": "d3", "strategy": "alphabeta(depth=2)" }),
+        );
+        assert_eq!(status, 200);
+        let plays = reply["plays"].as_array().unwrap();
+        assert_eq!(plays.len(), 3);
+        assert!(plays[0]["score"].as_f64() >= plays[2]["score"].as_f64());
+
+        // analysis is stopped in time too, with the scores from the deepest search that finished
+        let (status, reply) = post(
+            "/analyze",
+            json!({ "transcript": "d3", "strategy": "alphabeta(depth=30)", "movetime": 100 }),
+        );
+        assert_eq!(status, 200);
+        assert_eq!(reply["plays"].as_array().unwrap().len(), 3);
+
+        let (status, _) = post("/analyze", json!({ "strategy": "EXTERNAL(command=ls)" }));
+        assert_eq!(status, 403);
+
+        // nothing is searched without the resources to search it
+        for strategy in [
+            "alphabeta(table=100000)",
+            "alphabeta(threads=10000)",
+            "monte(threads=10000)",
+            "monte(rounds=100000000)",
+        ] {
+            let (status, reply) = post("/analyze", json!({ "strategy": strategy }));
+            assert_eq!(status, 400, "{}", strategy);
+            assert!(reply["error"].as_str().unwrap().contains("at most"));
+        }
+        let (status, _) = post("/analyze", json!({ "strategy": "alphabeta(table=64)" }));
+        assert_eq!(status, 200);
+    }
+
+    #[test]
+    fn test_no_moves() {
+        // dark has to pass: only light can flip anything at a1
+        let pass = json!({ "board": format!("-{}O", "X".repeat(62)), "turn": "dark" });
+        let (status, reply) = post("/bestmove", pass.clone());
+        assert_eq!(status, 200);
+        assert_eq!(reply["play"], "p");
+        let (status, reply) = post("/analyze", pass);
+        assert_eq!(status, 200);
+        assert_eq!(reply["plays"], json!([]));
+
+        let over = json!({ "board": "X".repeat(64), "turn": "dark" });
+        for path in ["/bestmove", "/analyze"] {
+            let (status, reply) = post(path, over.clone());
+            assert_eq!(status, 400);
+            assert_eq!(reply["error"], "The game is already over");
+        }
+    }
+
+    #[test]
+    fn test_check_headers() {
+        let json = Some("application/json; charset=utf-8");
+        assert!(check_headers("POST", json, None).is_ok());
+        assert!(check_headers("GET", None, None).is_ok());
+        assert!(check_headers("POST", json, Some("http://localhost:8000")).is_ok());
+        assert!(check_headers("POST", json, Some("http://127.0.0.1")).is_ok());
+        assert!(check_headers("POST", json, Some("http://[::1]:3000")).is_ok());
+
+        // what a page on any site can send without asking first
+        let (status, _) = check_headers("POST", Some("text/plain"), None).unwrap_err();
+        assert_eq!(status, 415);
+        assert_eq!(check_headers("POST", None, None).unwrap_err().0, 415);
+
+        for origin in [
+            "https://example.com",
+            "http://localhost.example.com",
+            "null",
+        ] {
+            let (status, _) = check_headers("POST", json, Some(origin)).unwrap_err();
+            assert_eq!(status, 403);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(handle("GET", "/schema", "").0, 200);
+        assert_eq!(handle("GET", "/moves", "").0, 405);
+        assert_eq!(handle("POST", "/nowhere", "").0, 404);
+        assert_eq!(handle("POST", "/moves", "[1, 2]").0, 400);
+        assert_eq!(handle("POST", "/moves", "{").0, 400);
+    }
+}
@@ -0,0 +1,101 @@
+//! Serves move generation, play, and analysis as JSON over HTTP, for tools that can't link
+//! against the library. Only listens on localhost.
+//!
+//! | Endpoint         | Takes                                     | Returns                       |
+//! |------------------|-------------------------------------------|-------------------------------|
+//! | `POST /moves`    | a game                                    | the valid moves               |
+//! | `POST /play`     | a game and a `play`                       | the game after the play       |
+//! | `POST /bestmove` | a game, `strategy`, and `movetime` in ms  | the strategy's play           |
+//! | `POST /analyze`  | a game, `strategy`, and `movetime` in ms  | every move scored, best first |
+//! | `GET /schema`    |                                           | JSON schemas for all of these |
+//!
+//! A game is a JSON object with a `transcript` (eg: `"d3,c5"` or `["d3", "c5"]`), or a `board`
+//! (the engine protocol's 64 character position) and a `turn` (`"dark"` or `"light"`). Errors
+//! come back as `{"error": "<message>"}`, with a 4xx or 5xx status.
+//!
+//! Since any web page open in a browser can send requests to localhost, POSTs have to be sent
+//! as `Content-Type: application/json`, requests from pages that aren't served from this
+//! machine are refused, and so is the `external` strategy, which would run any program.
+//! Strategies are stopped after `movetime`, can't ask for much memory or many threads, and
+//! only a few run at once.
+
+mod api;
+
+use clap::{App, ArgMatches};
+use std::io::{self, Read};
+use std::thread;
+use tiny_http::{Header, Request, Response, Server};
+
+const DEFAULT_PORT: u16 = 7476;
+
+/// The largest request body read, which is plenty for any game.
+const MAX_BODY: u64 = 64 * 1024;
+
+fn main() -> Result<(), io::Error> {
+    let config = get_args();
+
+    let port = match config.value_of("port") {
+        Some(port) => port.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid port: {}", port),
+            )
+        })?,
+        None => DEFAULT_PORT,
+    };
+
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| io::Error::new(io::ErrorKind::AddrNotAvailable, e.to_string()))?;
+    println!("deshttp: listening on http://{}", server.server_addr());
+
+    for request in server.incoming_requests() {
+        // strategies can take a while, so every request gets its own thread
+        thread::spawn(move || respond(request));
+    }
+
+    Ok(())
+}
+
+fn respond(mut request: Request) {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.to_string())
+    };
+    let (content_type, origin) = (header("Content-Type"), header("Origin"));
+    let method = request.method().to_string();
+
+    let mut body = String::new();
+    let checked = api::check_headers(&method, content_type.as_deref(), origin.as_deref());
+    let (status, reply) = match checked {
+        Err(reply) => reply,
+        Ok(()) => match request.as_reader().take(MAX_BODY).read_to_string(&mut body) {
+            Ok(_) => api::handle(&method, request.url(), &body),
+            Err(e) => (
+                400,
+                serde_json::json!({ "error": format!("Couldn't read the request: {}", e) }),
+            ),
+        },
+    };
+
+    let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+    let response = Response::from_string(reply.to_string())
+        .with_status_code(status)
+        .with_header(content_type);
+
+    // the client may have given up, and there's no one to tell
+    let _ = request.respond(response);
+}
+
+fn get_args() -> ArgMatches<'static> {
+    App::new("deshttp")
+        .version("0.1")
+        .author("Peat Bakke <peat@peat.org>")
+        .long_about(
+            "Serves valid moves, plays, best moves from any strategy, and analysis as JSON over HTTP, on localhost only. POST a game to /moves, /play, /bestmove, or /analyze; GET /schema describes every request and response.",
+        )
+        .args_from_usage("-p, --port=[PORT]               'The port to listen on (default 7476)'")
+        .get_matches()
+}
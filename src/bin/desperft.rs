@@ -0,0 +1,126 @@
+use clap::{App, ArgMatches};
+use desdemona::{parse_transcript, Game, Play, Position, PERFT};
+use rayon::prelude::*;
+use std::process;
+use std::time::Instant;
+
+const DEFAULT_DEPTH: usize = 9;
+
+fn main() {
+    let config = get_args();
+
+    let depth = match config.value_of("depth").map(str::parse) {
+        None => DEFAULT_DEPTH,
+        Some(Ok(depth)) if depth > 0 => depth,
+        Some(_) => exit("The depth should be a number, 1 or more."),
+    };
+
+    let game = match config.value_of("transcript") {
+        None => Game::new(),
+        Some(transcript) => parse_transcript(transcript)
+            .ok()
+            .and_then(|plays| Game::from_transcript(&plays))
+            .unwrap_or_else(|| exit("That transcript isn't a valid game.")),
+    };
+
+    // the published counts only apply to the opening position
+    let expected: &[u64] = if game.transcript.is_empty() {
+        println!("desperft: counting from the opening position");
+        &PERFT
+    } else {
+        println!("desperft: counting from {}", transcript(&game));
+        &[]
+    };
+
+    println!("depth            count         expected    seconds");
+    let mut failed = false;
+    for d in 1..=depth {
+        let started = Instant::now();
+        // a finished game has no children, but still counts once
+        let count: u64 = children(&game)
+            .par_iter()
+            .map(|(_, child)| child.perft(d - 1))
+            .sum::<u64>()
+            .max(1);
+        let seconds = started.elapsed().as_secs_f32();
+
+        let (expected, verdict) = match expected.get(d) {
+            Some(&expected) if expected == count => (expected.to_string(), "✅"),
+            Some(&expected) => {
+                failed = true;
+                (expected.to_string(), "❌")
+            }
+            None => ("-".to_owned(), ""),
+        };
+        let line = format!(
+            "{:>5} {:>16} {:>16} {:>10.2} {}",
+            d, count, expected, seconds, verdict
+        );
+        println!("{}", line.trim_end());
+    }
+
+    if config.is_present("divide") {
+        println!("\nplay  count at depth {}", depth);
+        let mut divided: Vec<(Play, u64)> = children(&game)
+            .par_iter()
+            .map(|(play, child)| (play.clone(), child.perft(depth - 1)))
+            .collect();
+        divided.sort_by_key(|(play, _)| play.to_string());
+        for (play, count) in divided {
+            println!("{:<4} {:>16}", play, count);
+        }
+    }
+
+    if failed {
+        exit("Move generation doesn't match the published counts!");
+    }
+}
+
+/// Every position one play from `game`: one per move, or a pass if there isn't one. Empty if
+/// the game is over.
+fn children(game: &Game) -> Vec<(Play, Game)> {
+    let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+
+    if moves.is_empty() {
+        if game.valid_moves(game.turn.opposite()).next().is_none() {
+            return vec![];
+        }
+        let mut passed = game.clone();
+        passed.pass();
+        return vec![(Play::Pass, passed)];
+    }
+
+    moves
+        .into_iter()
+        .map(|index| {
+            let mut child = game.clone();
+            child.play(index);
+            (Play::Move(Position::new(index)), child)
+        })
+        .collect()
+}
+
+fn transcript(game: &Game) -> String {
+    let plays: Vec<String> = game.transcript.iter().map(|p| p.to_string()).collect();
+    plays.join(",")
+}
+
+fn exit(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1)
+}
+
+fn get_args() -> ArgMatches<'static> {
+    App::new("desperft")
+        .version("0.1")
+        .author("Peat Bakke <peat@peat.org>")
+        .long_about(
+            "Counts the positions reachable in exactly N plays (perft), at each depth up to --depth, and checks them against the published counts from the opening position. Use cargo's --release flag; depth 10 takes a while.",
+        )
+        .args_from_usage(
+            "-d, --depth=[DEPTH]             'How many plays deep to count (default 9)'
+            --divide                        'Also break down the deepest count by first play'
+            --transcript=[TRANSCRIPT]       'Count from the position after these plays, eg: d3,c5'",
+        )
+        .get_matches()
+}
@@ -0,0 +1,82 @@
+//! A tiny reference engine speaking the `desdemona::protocol` engine protocol on stdin/stdout.
+//! It greedily plays whichever move flips the most discs, and reports the resulting disc
+//! difference as its score.
+
+use desdemona::protocol::{Command, Response};
+use desdemona::{Disc, Game, Play, Position};
+use std::io::{self, BufRead, Write};
+
+fn main() -> Result<(), io::Error> {
+    let mut game = Game::new();
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match line.parse::<Command>() {
+            Err(e) => Response::Error(e),
+            Ok(Command::Quit) => break,
+            Ok(Command::Hello) => Response::Hello {
+                name: "desref".to_owned(),
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+            },
+            Ok(Command::New) => {
+                game = Game::new();
+                Response::Ok
+            }
+            Ok(Command::Transcript(plays)) => match Game::from_transcript(&plays) {
+                Some(g) => {
+                    game = g;
+                    Response::Ok
+                }
+                None => Response::Error("Invalid transcript".to_owned()),
+            },
+            Ok(Command::Position(board, turn)) => {
+                game = Game::from_position(board, turn);
+                Response::Ok
+            }
+            Ok(Command::Play(play)) => match game.apply(&play) {
+                Ok(()) => Response::Ok,
+                Err(e) => Response::Error(e),
+            },
+            Ok(Command::Go(_)) => greedy_move(&game),
+            Ok(Command::Hint(_)) => Response::Error("Hints are not supported".to_owned()),
+        };
+
+        writeln!(stdout, "{}", response)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn greedy_move(game: &Game) -> Response {
+    let best = game
+        .valid_moves(game.turn)
+        .max_by_key(|index| game.flips_for(*index).len());
+
+    match best {
+        None => Response::Move {
+            play: Play::Pass,
+            score: None,
+        },
+        Some(index) => {
+            let mut next = game.clone();
+            next.play(index);
+
+            let difference = match game.turn {
+                Disc::Dark => next.dark as f32 - next.light as f32,
+                Disc::Light => next.light as f32 - next.dark as f32,
+            };
+
+            Response::Move {
+                play: Play::Move(Position::new(index)),
+                score: Some(difference),
+            }
+        }
+    }
+}
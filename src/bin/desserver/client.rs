@@ -0,0 +1,295 @@
+use desdemona::protocol::{Command, Response};
+use desdemona::{Disc, Game, Play};
+use std::fmt::{self, Display, Formatter};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a new connection has to introduce itself, and an engine has to answer anything
+/// that isn't a request for a move.
+pub const GRACE: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Speaks the server's line protocol
+    Human,
+    /// Speaks the engine protocol, with the server as its controller
+    Engine,
+}
+
+/// The ways a player can lose before the game is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forfeit {
+    Resigned,
+    /// Ran out of time on their clock
+    Time,
+    /// Tried an illegal play, or (for engines) said something that wasn't a move
+    Illegal,
+    Disconnected,
+}
+
+impl Display for Forfeit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Forfeit::Resigned => "resigned",
+            Forfeit::Time => "time",
+            Forfeit::Illegal => "illegal",
+            Forfeit::Disconnected => "disconnected",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// Someone connected to the server. Lines from the client are read on their own thread, so
+/// the server can wait for them with a deadline.
+pub struct Client {
+    pub name: String,
+    pub kind: Kind,
+    stream: TcpStream,
+    lines: Receiver<String>,
+    quit: bool,
+    /// Commands sent to an engine that it hasn't answered yet. Engines answer in order, so
+    /// after a timeout the late answers are skipped rather than taken for the next one's.
+    unanswered: usize,
+}
+
+impl Client {
+    /// Waits for a new connection to say who it is: `human <name>`, or `engine [<name>]`.
+    /// Engines are greeted with `hello`, and named after their answer if they didn't give one.
+    pub fn handshake(stream: TcpStream) -> Result<Self, String> {
+        let reader = BufReader::new(stream.try_clone().map_err(|e| e.to_string())?);
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let first = lines
+            .recv_timeout(GRACE)
+            .map_err(|_| "never said who they are".to_owned())?;
+        let first = first.trim();
+        let (keyword, name) = first.split_once(char::is_whitespace).unwrap_or((first, ""));
+        let kind = match keyword {
+            "human" if !name.trim().is_empty() => Kind::Human,
+            "engine" => Kind::Engine,
+            _ => {
+                let _ = writeln!(&stream, "error Expected \"human <name>\" or \"engine\"");
+                return Err(format!("unexpected introduction: {}", first));
+            }
+        };
+
+        let mut client = Client {
+            name: name.trim().to_owned(),
+            kind,
+            stream,
+            lines,
+            quit: false,
+            unanswered: 0,
+        };
+
+        if kind == Kind::Engine {
+            let deadline = Instant::now() + GRACE;
+            match client.request(&Command::Hello, deadline) {
+                Ok(Response::Hello { name, .. }) if client.name.is_empty() => client.name = name,
+                Ok(Response::Hello { .. }) => {}
+                _ => return Err("engine didn't answer hello".to_owned()),
+            }
+        }
+
+        Ok(client)
+    }
+
+    /// Sends a line to a human. Engines only get engine protocol commands.
+    pub fn tell(&mut self, line: impl Display) -> Result<(), Forfeit> {
+        if self.kind == Kind::Human {
+            self.send(line)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the client is still there and wants to keep playing. Anything they've said
+    /// between games is read and dropped, other than a human's `quit`.
+    pub fn is_connected(&mut self) -> bool {
+        loop {
+            match self.lines.try_recv() {
+                Ok(line) if self.kind == Kind::Human && line.trim() == "quit" => self.quit = true,
+                Ok(line) if self.kind == Kind::Engine => self.answered(&line),
+                Ok(_) => {}
+                Err(TryRecvError::Empty) => return !self.quit,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        }
+    }
+
+    /// Gets the client ready for a new game.
+    pub fn start(&mut self, id: usize, color: Disc, opponent: &str) -> Result<(), Forfeit> {
+        match self.kind {
+            Kind::Human => self.send(format_args!(
+                "start {} {} {}",
+                id,
+                color_name(color),
+                opponent
+            )),
+            Kind::Engine => match self.request(&Command::New, Instant::now() + GRACE)? {
+                Response::Ok => Ok(()),
+                _ => Err(Forfeit::Illegal),
+            },
+        }
+    }
+
+    /// Asks for a play, with `clock` left to make it. Humans are told about illegal plays and
+    /// can try again while their clock runs; engines lose for them.
+    pub fn request_play(&mut self, game: &Game, clock: Duration) -> Result<Play, Forfeit> {
+        let deadline = Instant::now() + clock;
+
+        match self.kind {
+            Kind::Human => {
+                let turn = if game.turn == Disc::Dark { 'X' } else { 'O' };
+                self.send(format_args!(
+                    "board {} {}",
+                    game.board.to_position_string(),
+                    turn
+                ))?;
+                self.send(format_args!("turn {}", clock.as_millis()))?;
+
+                loop {
+                    let line = self.recv(deadline)?;
+                    let line = line.trim();
+                    let (keyword, rest) =
+                        line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+                    match keyword {
+                        "play" => match rest.trim().parse().and_then(|play| checked(game, play)) {
+                            Ok(play) => return Ok(play),
+                            Err(e) => self.send(format_args!("error {}", e))?,
+                        },
+                        "resign" => return Err(Forfeit::Resigned),
+                        "quit" => {
+                            self.quit = true;
+                            return Err(Forfeit::Resigned);
+                        }
+                        "" => {}
+                        _ => self.send("error Expected \"play <move>\" or \"resign\"")?,
+                    }
+                }
+            }
+            Kind::Engine => {
+                let setup = Command::Transcript(game.transcript.clone());
+                if self.request(&setup, deadline)? != Response::Ok {
+                    return Err(Forfeit::Illegal);
+                }
+
+                // spread the clock over the moves still to come
+                let budget = clock / (game.empty as u32 / 2 + 1);
+                match self.request(&Command::Go(budget), deadline)? {
+                    Response::Move { play, .. } => {
+                        checked(game, play).map_err(|_| Forfeit::Illegal)
+                    }
+                    _ => Err(Forfeit::Illegal),
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, line: impl Display) -> Result<(), Forfeit> {
+        // one write per line, so lines don't go out in pieces
+        self.stream
+            .write_all(format!("{}\n", line).as_bytes())
+            .map_err(|_| Forfeit::Disconnected)
+    }
+
+    fn recv(&self, deadline: Instant) -> Result<String, Forfeit> {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match self.lines.recv_timeout(remaining) {
+            Ok(line) => Ok(line),
+            Err(RecvTimeoutError::Timeout) => Err(Forfeit::Time),
+            Err(RecvTimeoutError::Disconnected) => Err(Forfeit::Disconnected),
+        }
+    }
+
+    /// Sends an engine a command, and waits until `deadline` for its response, skipping any
+    /// `info` lines and late answers to earlier commands. Anything that doesn't parse is taken
+    /// as an illegal play.
+    fn request(&mut self, command: &Command, deadline: Instant) -> Result<Response, Forfeit> {
+        self.send(command)?;
+        self.unanswered += 1;
+
+        loop {
+            let line = self.recv(deadline)?;
+            let response = line.parse::<Response>();
+            self.answered(&line);
+
+            match response {
+                Ok(Response::Info(_)) => continue,
+                Ok(_) if self.unanswered > 0 => continue,
+                Ok(response) => return Ok(response),
+                Err(_) => return Err(Forfeit::Illegal),
+            }
+        }
+    }
+
+    /// Counts a line from an engine as an answer, unless it's an `info` line.
+    fn answered(&mut self, line: &str) {
+        if !matches!(line.parse::<Response>(), Ok(Response::Info(_))) {
+            self.unanswered = self.unanswered.saturating_sub(1);
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        if self.kind == Kind::Engine {
+            let _ = self.send(Command::Quit);
+        }
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+pub fn color_name(color: Disc) -> String {
+    format!("{:?}", color).to_lowercase()
+}
+
+/// Checks a play against the game, without making it.
+fn checked(game: &Game, play: Play) -> Result<Play, String> {
+    game.clone().apply(&play)?;
+    Ok(play)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_late_answers_are_skipped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut engine = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let mut commands = BufReader::new(engine.try_clone().unwrap()).lines();
+
+        writeln!(engine, "engine slow").unwrap();
+        let handshake = thread::spawn(move || Client::handshake(listener.accept().unwrap().0));
+        assert_eq!(commands.next().unwrap().unwrap(), "hello");
+        writeln!(engine, "hello slow 1.0").unwrap();
+        let mut client = handshake.join().unwrap().unwrap();
+
+        // the first game's move comes too late, and is lost on time
+        writeln!(engine, "ok").unwrap();
+        let game = Game::new();
+        let played = client.request_play(&game, Duration::from_millis(50));
+        assert_eq!(played, Err(Forfeit::Time));
+        writeln!(engine, "info thinking\nmove d3").unwrap();
+
+        // ... so it isn't the answer to anything in the next game
+        writeln!(engine, "ok\nok\nmove f5").unwrap();
+        assert!(client.is_connected());
+        assert_eq!(client.start(2, Disc::Dark, "someone"), Ok(()));
+        let played = client.request_play(&game, Duration::from_secs(10));
+        assert_eq!(played, Ok("f5".parse().unwrap()));
+    }
+}
@@ -0,0 +1,221 @@
+use crate::referee::Outcome;
+use desdemona::ggf::GgfGame;
+use desdemona::Disc;
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A player's results so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Record {
+    pub wins: usize,
+    pub losses: usize,
+    pub draws: usize,
+}
+
+impl Record {
+    pub fn games(&self) -> usize {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Wins count one point, and draws half.
+    pub fn points(&self) -> f32 {
+        self.wins as f32 + self.draws as f32 / 2.0
+    }
+}
+
+/// Every finished game, written to a GGF archive one per line, and the standings they add up
+/// to. The standings are rebuilt from the archive when the server starts, so the ladder carries
+/// on from where it left off.
+pub struct Ladder {
+    path: PathBuf,
+    standings: HashMap<String, Record>,
+}
+
+impl Ladder {
+    /// Opens the archive at `path`, reading any games already in it.
+    pub fn open(path: PathBuf) -> Result<Self, String> {
+        let mut ladder = Ladder {
+            path,
+            standings: HashMap::new(),
+        };
+
+        let text = match fs::read_to_string(&ladder.path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ladder),
+            Err(e) => return Err(format!("Couldn't read {}: {}", ladder.path.display(), e)),
+        };
+
+        let games = GgfGame::parse_all(&text)
+            .map_err(|e| format!("Couldn't load {}: {}", ladder.path.display(), e))?;
+        for game in games {
+            // games without a readable result (eg: aborted, `?`) don't count
+            if let (Some(dark), Some(light), Some(winner)) = (
+                game.tag("PB"),
+                game.tag("PW"),
+                game.tag("RE").and_then(winner),
+            ) {
+                ladder.tally(dark, light, winner);
+            }
+        }
+
+        Ok(ladder)
+    }
+
+    pub fn get(&self, name: &str) -> Record {
+        self.standings.get(name).copied().unwrap_or_default()
+    }
+
+    /// Adds a finished game to the archive and the standings.
+    pub fn record(&mut self, dark: &str, light: &str, outcome: &Outcome) -> io::Result<()> {
+        // every play was checked as it was made, so the transcript always replays
+        let mut ggf = GgfGame::from_transcript(&outcome.game.transcript)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        for (tag, value) in [
+            ("PC", "desserver".to_owned()),
+            ("DT", date.to_string()),
+            ("PB", dark.to_owned()),
+            ("PW", light.to_owned()),
+            ("RE", outcome.result()),
+            ("TY", "8".to_owned()),
+        ] {
+            ggf.tags.push((tag.to_owned(), value));
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(format!("{}\n", ggf).as_bytes())?;
+
+        self.tally(dark, light, outcome.winner());
+        Ok(())
+    }
+
+    fn tally(&mut self, dark: &str, light: &str, winner: Option<Disc>) {
+        let mut dark_record = self.get(dark);
+        let mut light_record = self.get(light);
+
+        match winner {
+            Some(Disc::Dark) => {
+                dark_record.wins += 1;
+                light_record.losses += 1;
+            }
+            Some(Disc::Light) => {
+                dark_record.losses += 1;
+                light_record.wins += 1;
+            }
+            None => {
+                dark_record.draws += 1;
+                light_record.draws += 1;
+            }
+        }
+
+        self.standings.insert(dark.to_owned(), dark_record);
+        self.standings.insert(light.to_owned(), light_record);
+    }
+}
+
+impl Display for Ladder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut standings: Vec<(&String, &Record)> = self.standings.iter().collect();
+        standings.sort_by(|(a_name, a), (b_name, b)| {
+            b.points()
+                .total_cmp(&a.points())
+                .then(a.games().cmp(&b.games()))
+                .then(a_name.cmp(b_name))
+        });
+
+        writeln!(f, "  #  Points  Won  Lost  Drawn  Player")?;
+        for (n, (name, record)) in standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>3}  {:>6.1}  {:>3}  {:>4}  {:>5}  {}",
+                n + 1,
+                record.points(),
+                record.wins,
+                record.losses,
+                record.draws,
+                name
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Who won (`None` for a draw), from a GGF result such as `+10` or `-64:t`, or `None` if the
+/// result can't be read.
+fn winner(result: &str) -> Option<Option<Disc>> {
+    let score: f32 = result.split(':').next()?.trim().parse().ok()?;
+
+    if score > 0.0 {
+        Some(Some(Disc::Dark))
+    } else if score < 0.0 {
+        Some(Some(Disc::Light))
+    } else {
+        Some(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::client::Forfeit;
+    use desdemona::{parse_transcript, Game};
+
+    #[test]
+    fn test_winner() {
+        assert_eq!(winner("+10"), Some(Some(Disc::Dark)));
+        assert_eq!(winner("-64:t"), Some(Some(Disc::Light)));
+        assert_eq!(winner("+0.000"), Some(None));
+        assert_eq!(winner("?"), None);
+    }
+
+    #[test]
+    fn test_record_and_reopen() {
+        let path =
+            std::env::temp_dir().join(format!("desserver-ladder-{}.ggf", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut ladder = Ladder::open(path.clone()).unwrap();
+        let game = Game::from_transcript(&parse_transcript("d3,c5").unwrap()).unwrap();
+        let resigned = Outcome {
+            game,
+            forfeit: Some((Disc::Light, Forfeit::Resigned)),
+        };
+        ladder.record("alphabeta", "monte", &resigned).unwrap();
+        ladder.record("monte", "alphabeta", &resigned).unwrap();
+
+        // a game with no result isn't counted as a draw
+        let mut aborted = GgfGame::from_transcript(&resigned.game.transcript).unwrap();
+        for (tag, value) in [("PB", "monte"), ("PW", "alphabeta"), ("RE", "?")] {
+            aborted.tags.push((tag.to_owned(), value.to_owned()));
+        }
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{}", aborted).unwrap();
+
+        let reopened = Ladder::open(path.clone()).unwrap();
+        assert_eq!(reopened.get("alphabeta"), ladder.get("alphabeta"));
+        assert_eq!(
+            reopened.get("monte"),
+            Record {
+                wins: 1,
+                losses: 1,
+                draws: 0
+            }
+        );
+        assert!(reopened.to_string().contains("monte"));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! Hosts games between anyone who connects, pairing them up as they arrive.
+//!
+//! Clients introduce themselves with their first line. Humans (or anything else speaking the
+//! server's line protocol) send `human <name>`; engines send `engine [<name>]` and are then
+//! driven with the [engine protocol](desdemona::protocol), which is what `desengine --connect`
+//! does. Humans are sent:
+//!
+//! | Line                              | Meaning                                                |
+//! |-----------------------------------|--------------------------------------------------------|
+//! | `waiting`                         | in the queue for an opponent                           |
+//! | `start <id> <dark/light> <name>`  | a game has started, with your color and your opponent  |
+//! | `board <position> <X/O>`          | the board, just before your turn                       |
+//! | `turn <milliseconds>`             | your move, with this much left on your clock           |
+//! | `played <dark/light> <play>`      | a play was made, by either side                        |
+//! | `error <message>`                 | your last line was rejected                            |
+//! | `over <dark> <light> <winner> <reason>` | the game is over: the disc counts, `dark`, `light` or `draw`, and `complete`, `resigned`, `time`, `illegal`, or `disconnected` |
+//!
+//! and can send `play <move>`, `resign`, or `quit` to leave. Everyone still connected after a
+//! game goes back in the queue, so engines left connected keep playing each other.
+
+mod client;
+mod ladder;
+mod referee;
+
+use clap::{App, ArgMatches};
+use client::Client;
+use ladder::Ladder;
+use referee::referee;
+use std::collections::VecDeque;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_PORT: u16 = 7475;
+const DEFAULT_CLOCK: u64 = 300;
+const DEFAULT_RECORD: &str = "desserver.ggf";
+
+struct Server {
+    /// How long each player has for all of their moves in a game
+    clock: Duration,
+    queue: Mutex<VecDeque<Client>>,
+    queued: Condvar,
+    ladder: Mutex<Ladder>,
+    games: AtomicUsize,
+}
+
+fn main() -> Result<(), io::Error> {
+    let config = get_args();
+
+    let port = parse_or(&config, "port", DEFAULT_PORT)?;
+    let clock = Duration::from_secs(parse_or(&config, "clock", DEFAULT_CLOCK)?);
+    let record = PathBuf::from(config.value_of("record").unwrap_or(DEFAULT_RECORD));
+
+    let ladder = Ladder::open(record).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    print!("{}", ladder);
+
+    let server = Arc::new(Server {
+        clock,
+        queue: Mutex::new(VecDeque::new()),
+        queued: Condvar::new(),
+        ladder: Mutex::new(ladder),
+        games: AtomicUsize::new(0),
+    });
+
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    println!("desserver: listening on {}", listener.local_addr()?);
+
+    let matchmaker = server.clone();
+    thread::spawn(move || matchmaker.matchmake());
+
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let server = server.clone();
+        thread::spawn(move || server.welcome(stream));
+    }
+
+    Ok(())
+}
+
+impl Server {
+    /// Finds out who's connected, and puts them in the queue.
+    fn welcome(&self, stream: TcpStream) {
+        let peer = stream
+            .peer_addr()
+            .map(|a| a.to_string())
+            .unwrap_or_default();
+
+        match Client::handshake(stream) {
+            Ok(client) => {
+                println!("{} joined from {}", client.name, peer);
+                self.enqueue(client);
+            }
+            Err(e) => println!("Turned away {}: {}", peer, e),
+        }
+    }
+
+    fn enqueue(&self, mut client: Client) {
+        if client.tell("waiting").is_ok() {
+            self.queue.lock().unwrap().push_back(client);
+            self.queued.notify_one();
+        }
+    }
+
+    /// Pairs up clients in the order they joined the queue, starting a game for each pair.
+    fn matchmake(self: Arc<Self>) {
+        loop {
+            let (mut dark, mut light) = {
+                let mut queue = self.queue.lock().unwrap();
+                loop {
+                    queue.retain_mut(|client| client.is_connected());
+                    if queue.len() >= 2 {
+                        break;
+                    }
+                    queue = self.queued.wait(queue).unwrap();
+                }
+                (queue.pop_front().unwrap(), queue.pop_front().unwrap())
+            };
+
+            if rand::random() {
+                std::mem::swap(&mut dark, &mut light);
+            }
+
+            let id = self.games.fetch_add(1, Ordering::SeqCst) + 1;
+            let server = self.clone();
+            thread::spawn(move || server.host(id, dark, light));
+        }
+    }
+
+    /// Plays a game, records it, and puts both players back in the queue.
+    fn host(&self, id: usize, mut dark: Client, mut light: Client) {
+        println!(
+            "Game {}: {} (dark) vs {} (light)",
+            id, dark.name, light.name
+        );
+        let outcome = referee(id, &mut dark, &mut light, self.clock);
+
+        let reason = match outcome.forfeit {
+            Some((loser, forfeit)) => format!(" ({:?} {})", loser, forfeit),
+            None => String::new(),
+        };
+        println!(
+            "Game {}: {} {} - {} {}{}",
+            id, dark.name, outcome.game.dark, outcome.game.light, light.name, reason
+        );
+
+        {
+            let mut ladder = self.ladder.lock().unwrap();
+            if let Err(e) = ladder.record(&dark.name, &light.name, &outcome) {
+                println!("Couldn't record game {}: {}", id, e);
+            }
+            print!("{}", ladder);
+        }
+
+        for mut client in [dark, light] {
+            if client.is_connected() {
+                self.enqueue(client);
+            } else {
+                println!("{} left", client.name);
+            }
+        }
+    }
+}
+
+fn parse_or<T: std::str::FromStr>(config: &ArgMatches, name: &str, default: T) -> io::Result<T> {
+    match config.value_of(name) {
+        Some(value) => value.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid --{}: {}", name, value),
+            )
+        }),
+        None => Ok(default),
+    }
+}
+
+fn get_args() -> ArgMatches<'static> {
+    App::new("desserver")
+        .version("0.1")
+        .author("Peat Bakke <peat@peat.org>")
+        .long_about(
+            "Hosts games for anyone who connects, pairing players up as they arrive. Engines join with `desengine --connect <address>`, and humans by sending \"human <name>\" (eg: with netcat). Legality and clocks are enforced by the server, every finished game is written to a GGF file, and the standings are printed after each game.",
+        )
+        .args_from_usage(
+            "-p, --port=[PORT]               'The port to listen on (default 7475)'
+            --clock=[SECONDS]               'Each player's time for a whole game (default 300)'
+            --record=[FILE]                 'Where finished games are written, and the ladder is read from (default desserver.ggf)'",
+        )
+        .get_matches()
+}
@@ -0,0 +1,205 @@
+use crate::client::{color_name, Client, Forfeit};
+use desdemona::{Disc, Game, Play};
+use std::time::{Duration, Instant};
+
+/// How a game ended.
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    pub game: Game,
+    /// Who lost before the end of the game, and why
+    pub forfeit: Option<(Disc, Forfeit)>,
+}
+
+impl Outcome {
+    pub fn winner(&self) -> Option<Disc> {
+        match self.forfeit {
+            Some((loser, _)) => Some(loser.opposite()),
+            None if self.game.dark > self.game.light => Some(Disc::Dark),
+            None if self.game.light > self.game.dark => Some(Disc::Light),
+            None => None,
+        }
+    }
+
+    /// The result as GGF writes it, from dark's point of view: the final disc difference, or
+    /// all 64 discs to the winner of a forfeit, marked `:t` for time and `:r` for anything else.
+    pub fn result(&self) -> String {
+        match self.forfeit {
+            Some((loser, forfeit)) => {
+                let sign = if loser == Disc::Dark { '-' } else { '+' };
+                let mark = if forfeit == Forfeit::Time { 't' } else { 'r' };
+                format!("{}64:{}", sign, mark)
+            }
+            None => format!("{:+}", self.game.dark as i64 - self.game.light as i64),
+        }
+    }
+
+    /// The line sent to humans at the end of the game, eg: `over 40 24 dark complete`
+    pub fn summary(&self) -> String {
+        let winner = self.winner().map(color_name);
+        let reason = self.forfeit.map(|(_, forfeit)| forfeit.to_string());
+        format!(
+            "over {} {} {} {}",
+            self.game.dark,
+            self.game.light,
+            winner.as_deref().unwrap_or("draw"),
+            reason.as_deref().unwrap_or("complete")
+        )
+    }
+}
+
+/// Plays a game between two clients, with `clock` for each of them. Every play is checked
+/// with [Game::apply], passes are made for players with no moves, and the time a player takes
+/// comes off their clock.
+pub fn referee(id: usize, dark: &mut Client, light: &mut Client, clock: Duration) -> Outcome {
+    let mut game = Game::new();
+    let mut clocks = [clock, clock];
+
+    if let Err(forfeit) = dark.start(id, Disc::Dark, &light.name) {
+        return finish(game, Some((Disc::Dark, forfeit)), dark, light);
+    }
+    if let Err(forfeit) = light.start(id, Disc::Light, &dark.name) {
+        return finish(game, Some((Disc::Light, forfeit)), dark, light);
+    }
+
+    while !game.is_complete {
+        let player = game.turn;
+        let side = match player {
+            Disc::Dark => 0,
+            Disc::Light => 1,
+        };
+
+        let play = if game.valid_moves(player).next().is_none() {
+            Play::Pass
+        } else {
+            let client = match player {
+                Disc::Dark => &mut *dark,
+                Disc::Light => &mut *light,
+            };
+
+            let started = Instant::now();
+            let requested = client.request_play(&game, clocks[side]);
+            let elapsed = started.elapsed();
+
+            match requested {
+                Ok(_) if elapsed > clocks[side] => {
+                    return finish(game, Some((player, Forfeit::Time)), dark, light)
+                }
+                Ok(play) => {
+                    clocks[side] -= elapsed;
+                    play
+                }
+                Err(forfeit) => return finish(game, Some((player, forfeit)), dark, light),
+            }
+        };
+
+        // the client checked it, but the server's game has the final word
+        if game.apply(&play).is_err() {
+            return finish(game, Some((player, Forfeit::Illegal)), dark, light);
+        }
+
+        let line = format!("played {} {}", color_name(player), play);
+        let _ = dark.tell(&line);
+        let _ = light.tell(&line);
+    }
+
+    finish(game, None, dark, light)
+}
+
+fn finish(
+    game: Game,
+    forfeit: Option<(Disc, Forfeit)>,
+    dark: &mut Client,
+    light: &mut Client,
+) -> Outcome {
+    let outcome = Outcome { game, forfeit };
+    let summary = outcome.summary();
+    let _ = dark.tell(&summary);
+    let _ = light.tell(&summary);
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::io::{BufRead, BufReader, Lines, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    /// Connects a human to `listener`, returning the server's side and the human's.
+    fn human(
+        listener: &TcpListener,
+        name: &str,
+    ) -> (Client, TcpStream, Lines<BufReader<TcpStream>>) {
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        writeln!(stream, "human {}", name).unwrap();
+        let client = Client::handshake(listener.accept().unwrap().0).unwrap();
+        let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+        (client, stream, lines)
+    }
+
+    /// Plays the first valid move whenever it's our turn, after trying an illegal one.
+    fn play_first_moves(mut stream: TcpStream, lines: Lines<BufReader<TcpStream>>) -> String {
+        let mut game = None;
+        for line in lines.map_while(Result::ok) {
+            let (keyword, rest) = line.split_once(' ').unwrap_or((&line, ""));
+            match keyword {
+                "board" => {
+                    let (board, turn) = rest.split_once(' ').unwrap();
+                    let board = desdemona::Board::from_position_string(board).unwrap();
+                    let turn = if turn == "X" { Disc::Dark } else { Disc::Light };
+                    game = Some(Game::from_position(board, turn));
+                }
+                "turn" => {
+                    let game = game.as_ref().unwrap();
+                    let index = game.valid_moves(game.turn).next().unwrap();
+                    writeln!(stream, "play d4").unwrap();
+                    writeln!(stream, "play {}", desdemona::Position::new(index)).unwrap();
+                }
+                "over" => return line,
+                _ => {}
+            }
+        }
+        panic!("the game didn't finish");
+    }
+
+    #[test]
+    fn test_referee_humans() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (mut dark, dark_stream, dark_lines) = human(&listener, "alice");
+        let (mut light, light_stream, light_lines) = human(&listener, "bob");
+        assert_eq!(light.name, "bob");
+
+        let dark_player = thread::spawn(move || play_first_moves(dark_stream, dark_lines));
+        let light_player = thread::spawn(move || play_first_moves(light_stream, light_lines));
+
+        let outcome = referee(1, &mut dark, &mut light, Duration::from_secs(30));
+        assert!(outcome.game.is_complete);
+        assert_eq!(outcome.forfeit, None);
+
+        let summary = dark_player.join().unwrap();
+        assert_eq!(summary, outcome.summary());
+        assert_eq!(light_player.join().unwrap(), summary);
+        assert!(summary.ends_with("complete"));
+    }
+
+    #[test]
+    fn test_referee_clock_and_resign() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let (mut dark, _dark_stream, _dark_lines) = human(&listener, "slow");
+        let (mut light, mut light_stream, _light_lines) = human(&listener, "quitter");
+
+        // dark never moves
+        let outcome = referee(1, &mut dark, &mut light, Duration::from_millis(100));
+        assert_eq!(outcome.forfeit, Some((Disc::Dark, Forfeit::Time)));
+        assert_eq!(outcome.winner(), Some(Disc::Light));
+        assert_eq!(outcome.result(), "-64:t");
+
+        // light resigns before they're asked
+        writeln!(light_stream, "resign").unwrap();
+        let outcome = referee(2, &mut light, &mut dark, Duration::from_secs(30));
+        assert_eq!(outcome.forfeit, Some((Disc::Dark, Forfeit::Resigned)));
+        assert_eq!(outcome.result(), "-64:r");
+        assert!(dark.is_connected());
+    }
+}
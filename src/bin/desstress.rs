@@ -64,7 +64,7 @@ fn divergence_grinder() -> Option<(Game, Game)> {
 
     // complete a random move game
     while !game.is_complete {
-        match game.valid_moves(game.turn).into_iter().choose(&mut rng) {
+        match game.valid_moves(game.turn).choose(&mut rng) {
             Some(vm) => game.play(vm),
             None => game.pass(),
         };
@@ -106,7 +106,6 @@ fn parallel_bench() -> Result<(), io::Error> {
 
     let started = Instant::now();
     let _ = (0..divisor)
-        .into_iter()
         .collect::<Vec<usize>>()
         .par_iter()
         .map(|_| Random {}.bench(replay_loops))
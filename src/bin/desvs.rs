@@ -2,98 +2,142 @@ use clap::{App, ArgMatches};
 use desdemona::strategies::*;
 use desdemona::{Disc, Game};
 use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
 use std::io;
+use std::thread;
 
 const DEFAULT_GAMES: usize = 1000;
 
 pub fn main() -> Result<(), io::Error> {
     let config = get_args();
 
-    let dark_strategy: Box<dyn Strategy> = dark_strategy(&config)?;
-    let light_strategy: Box<dyn Strategy> = light_strategy(&config)?;
+    let dark_config = dark_strategy(&config)?;
+    let light_config = light_strategy(&config)?;
     let game_count: usize = match config.value_of("games") {
         Some(input) => input.parse().unwrap_or(DEFAULT_GAMES),
         None => DEFAULT_GAMES,
     };
+    let jobs = match config.value_of("jobs") {
+        Some(input) => input.parse().ok().filter(|&jobs| jobs > 0).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid number of jobs: {}", input),
+            )
+        })?,
+        None => default_jobs(&[&dark_config, &light_config]),
+    };
+
+    let dark_strategy = dark_config.to_string();
+    let light_strategy = light_config.to_string();
 
     println!(
-        "desvs: playing dark ({}) vs light ({}) for {} games",
-        dark_strategy.name(),
-        light_strategy.name(),
-        game_count,
+        "desvs: playing dark ({}) vs light ({}) for {} games, {} at a time",
+        dark_strategy, light_strategy, game_count, jobs,
     );
 
-    let games_iter = OpponentIterator::new(dark_strategy.name(), light_strategy.name(), game_count);
+    let games_iter = OpponentIterator::new(&dark_strategy, &light_strategy, game_count);
 
     let games: Vec<_> = games_iter.into_iter().collect();
 
-    games.into_par_iter().for_each(|(dark, light)| {
-        let game = run_game(&dark, &light);
-
-        // print out a CSV of stats
-        println!(
-            "{},{},{},{},{}",
-            dark_strategy.name(),
-            light_strategy.name(),
-            game.dark,
-            game.light,
-            game.transcript
-                .iter()
-                .map(|p| format!("{}", p))
-                .collect::<Vec<String>>()
-                .join("")
-        );
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    pool.install(|| {
+        games.into_par_iter().for_each(|(dark, light)| {
+            let game = run_game(&dark, &light);
+
+            // print out a CSV of stats
+            println!(
+                "{},{},{},{},{}",
+                csv_field(&dark),
+                csv_field(&light),
+                game.dark,
+                game.light,
+                game.transcript
+                    .iter()
+                    .map(|p| format!("{}", p))
+                    .collect::<Vec<String>>()
+                    .join("")
+            );
+        })
     });
 
     Ok(())
 }
 
+/// One game per core, unless the strategies search with several threads of their own, in
+/// which case fewer games at once keeps the total from oversubscribing the cores.
+fn default_jobs(strategies: &[&Config]) -> usize {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    let threads = strategies
+        .iter()
+        .filter_map(|config| config.value("threads")?.parse::<usize>().ok())
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    (cores / threads).max(1)
+}
+
 fn get_args() -> ArgMatches<'static> {
+    let about = format!(
+        "Plays two strategies against each other. Available strategies (and their parameters) are:\n{}\nParameters are given in parentheses, eg: monte(rounds=2000,threads=4)",
+        registry().help()
+    );
+
     App::new("desvs")
         .version("0.1")
         .author("Peat Bakke <peat@peat.org>")
-        .long_about(
-            "Plays two strategies against each other. Available strategies are:\nconstrain, corners, maximize, minimize, monte, random, and simple",
-        )
+        .long_about(about.as_str())
         .args_from_usage(
             "-g, --games=[COUNT]        'How many games to play (default 1,000)'
+            -j, --jobs=[COUNT]         'How many games to play at once (default: one per core, or fewer for strategies using several threads)'
             -l, --light=<STRATEGY>       'Determine the light player's strategy'
             -d, --dark=<STRATEGY>       'Determine the dark player's strategy'",
         )
         .get_matches()
 }
 
-fn light_strategy(config: &ArgMatches) -> Result<Box<dyn Strategy>, io::Error> {
+fn light_strategy(config: &ArgMatches) -> Result<Config, io::Error> {
     parse_strategy(config, "light")
 }
 
-fn dark_strategy(config: &ArgMatches) -> Result<Box<dyn Strategy>, io::Error> {
+fn dark_strategy(config: &ArgMatches) -> Result<Config, io::Error> {
     parse_strategy(config, "dark")
 }
 
-fn parse_strategy(config: &ArgMatches, name: &str) -> Result<Box<dyn Strategy>, io::Error> {
-    let strategy: Box<dyn Strategy> = match config.value_of(name) {
-        None => Box::new(Minimize {}),
-        Some(strategy) => match Strategies::from_name(strategy) {
-            Some(s) => s,
-            None => {
-                let error = format!(
-                    "Unknown strategy {} -- try random, constrain, minimize, maximize, monte, or simple.",
-                    strategy
-                );
-                return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
-            }
-        },
+/// Parses and validates the strategy for a player, returning its canonical specification.
+fn parse_strategy(config: &ArgMatches, name: &str) -> Result<Config, io::Error> {
+    let spec = config.value_of(name).unwrap_or("minimize");
+
+    let invalid = |e: ConfigError| {
+        let error = format!("{} -- available strategies are:\n{}", e, registry().help());
+        io::Error::new(io::ErrorKind::InvalidInput, error)
     };
 
+    // build it once up front so bad names or parameters are reported before any games run
+    let strategy = Config::parse(spec).map_err(invalid)?;
+    from_name(&strategy.to_string()).map_err(invalid)?;
+
     Ok(strategy)
 }
 
+/// Quotes strategy specifications that contain commas, eg: `monte(rounds=10,threads=2)`
+fn csv_field(value: &str) -> String {
+    if value.contains(',') {
+        format!("\"{}\"", value)
+    } else {
+        value.to_owned()
+    }
+}
+
 fn run_game(dark_strategy_name: &str, light_strategy_name: &str) -> Game {
     let mut game = Game::new();
 
-    let mut dark_strategy = Strategies::from_name(dark_strategy_name).unwrap();
-    let mut light_strategy = Strategies::from_name(light_strategy_name).unwrap();
+    let mut dark_strategy = from_name(dark_strategy_name).unwrap();
+    let mut light_strategy = from_name(light_strategy_name).unwrap();
 
     while !game.is_complete {
         let strategy = match game.turn {
@@ -120,8 +164,8 @@ struct OpponentIterator {
 impl OpponentIterator {
     pub fn new(dark_strategy: &str, light_strategy: &str, games: usize) -> Self {
         Self {
-            dark_strategy_name: dark_strategy.to_ascii_lowercase(),
-            light_strategy_name: light_strategy.to_ascii_lowercase(),
+            dark_strategy_name: dark_strategy.to_owned(),
+            light_strategy_name: light_strategy.to_owned(),
             games,
             current: 0,
         }
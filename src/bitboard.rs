@@ -0,0 +1,410 @@
+//! Move generation on bitboards, without allocating.
+//!
+//! A bitboard is a `u64` with bit _n_ set for the square at index _n_, so a whole line of
+//! discs can be shifted and masked at once. [Board] stores one per color, and everything here
+//! works on those masks directly:
+//!
+//! * [moves](crate::bitboard::moves) and [flips](crate::bitboard::flips) are the rules, as
+//!   plain functions on masks
+//! * [MoveList] holds a player's moves in a fixed-size array, for sorting and iterating
+//! * [State] is a board and the player to move, without the transcript that [Game] keeps. It's
+//!   `Copy`, so searches can try a move on a copy instead of cloning a whole game.
+//!
+//! [Game]'s methods are built on these, and are still the convenient way to play a game.
+
+use crate::data;
+use crate::{Board, Disc, Game};
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// Every square except the a file (x = 0).
+const NOT_A_FILE: u64 = 0xfefe_fefe_fefe_fefe;
+
+/// Every square except the h file (x = 7).
+const NOT_H_FILE: u64 = 0x7f7f_7f7f_7f7f_7f7f;
+
+/// The eight directions, as the change in index for one step and the squares a step can
+/// land on without wrapping around the edge of the board.
+const DIRECTIONS: [(i32, u64); 8] = [
+    (-8, !0),         // north
+    (-7, NOT_A_FILE), // north east
+    (1, NOT_A_FILE),  // east
+    (9, NOT_A_FILE),  // south east
+    (8, !0),          // south
+    (7, NOT_H_FILE),  // south west
+    (-1, NOT_H_FILE), // west
+    (-9, NOT_H_FILE), // north west
+];
+
+/// Moves every square in `bits` one step in a direction.
+#[inline]
+fn shift(bits: u64, (step, mask): (i32, u64)) -> u64 {
+    if step > 0 {
+        (bits << step) & mask
+    } else {
+        (bits >> -step) & mask
+    }
+}
+
+/// The squares the player with discs at `mine` can move to, when the opponent has `theirs`.
+pub fn moves(mine: u64, theirs: u64) -> u64 {
+    let empty = !(mine | theirs);
+    let mut moves = 0;
+
+    for direction in DIRECTIONS {
+        // runs of the opponent's discs starting next to one of ours; a run can't be longer
+        // than six squares
+        let mut run = shift(mine, direction) & theirs;
+        for _ in 0..5 {
+            run |= shift(run, direction) & theirs;
+        }
+
+        // ... and an empty square at the end of one is a move
+        moves |= shift(run, direction) & empty;
+    }
+
+    moves
+}
+
+/// The discs flipped if the player with discs at `mine` plays at `index`. Zero if the
+/// square is taken, or it isn't a move.
+pub fn flips(mine: u64, theirs: u64, index: usize) -> u64 {
+    let placed = 1 << index;
+    if (mine | theirs) & placed != 0 {
+        return 0;
+    }
+
+    let mut flips = 0;
+    for direction in DIRECTIONS {
+        let mut run = 0;
+        let mut next = shift(placed, direction);
+        while next & theirs != 0 {
+            run |= next;
+            next = shift(next, direction);
+        }
+
+        // the run only flips if it's closed off by one of ours
+        if next & mine != 0 {
+            flips |= run;
+        }
+    }
+
+    flips
+}
+
+/// An `Iterator` over the indexes of the squares in a mask, lowest first.
+pub(crate) fn squares(mask: u64) -> Squares {
+    Squares(mask)
+}
+
+pub(crate) struct Squares(u64);
+
+impl Iterator for Squares {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count_ones() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for Squares {}
+
+/// A list of moves, by index, in a fixed-size array rather than a `Vec`. It dereferences to
+/// a slice, so it can be iterated, sorted, and indexed like one.
+#[derive(Clone, Copy)]
+pub struct MoveList {
+    moves: [usize; 64],
+    len: usize,
+}
+
+impl MoveList {
+    /// The squares in `mask`, lowest index first.
+    pub fn from_mask(mask: u64) -> Self {
+        let mut list = Self {
+            moves: [0; 64],
+            len: 0,
+        };
+
+        for index in squares(mask) {
+            list.moves[list.len] = index;
+            list.len += 1;
+        }
+
+        list
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [usize];
+
+    fn deref(&self) -> &[usize] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [usize] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a usize;
+    type IntoIter = std::slice::Iter<'a, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Debug for MoveList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// A board and the player to move, and nothing else. See the [module docs](self).
+///
+/// Like [Game::play()], [State::play()] doesn't check that a move is valid, and a [State]
+/// doesn't track whether the game is complete; use [State::is_over()] to check.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct State {
+    /// The game board
+    pub board: Board,
+    /// Which player is currently playing
+    pub turn: Disc,
+}
+
+impl State {
+    /// The opening position, with dark to play.
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+            turn: Disc::Dark,
+        }
+    }
+
+    /// The valid moves for the player to move.
+    pub fn moves(&self) -> MoveList {
+        MoveList::from_mask(self.move_mask(self.turn))
+    }
+
+    /// A mask of the valid moves for `player`, whether or not it's their turn.
+    pub fn move_mask(&self, player: Disc) -> u64 {
+        moves(
+            self.board.mask(Some(player)),
+            self.board.mask(Some(player.opposite())),
+        )
+    }
+
+    /// A mask of the discs flipped if the player to move plays at `index`.
+    pub fn flips(&self, index: usize) -> u64 {
+        flips(
+            self.board.mask(Some(self.turn)),
+            self.board.mask(Some(self.turn.opposite())),
+            index,
+        )
+    }
+
+    /// Plays a disc at the given index for the player to move, flipping the opponent's discs,
+    /// and returns the mask of flipped discs.
+    pub fn play(&mut self, index: usize) -> u64 {
+        let flips = self.flips(index);
+        self.board.place(self.turn, flips | 1 << index);
+        self.turn = self.turn.opposite();
+        flips
+    }
+
+    /// Hands the turn to the other player.
+    pub fn pass(&mut self) {
+        self.turn = self.turn.opposite();
+    }
+
+    /// Whether neither player can move.
+    pub fn is_over(&self) -> bool {
+        self.move_mask(Disc::Dark) == 0 && self.move_mask(Disc::Light) == 0
+    }
+
+    /// A Zobrist hash of the board and the player to move, for keying a
+    /// [TranspositionTable](crate::strategies::TranspositionTable).
+    pub fn zobrist(&self) -> u64 {
+        let mut hash = match self.turn {
+            Disc::Dark => data::ZOBRIST_DARK_TO_MOVE,
+            Disc::Light => 0,
+        };
+
+        for (keys, disc) in data::ZOBRIST_DISCS.iter().zip([Disc::Dark, Disc::Light]) {
+            for index in squares(self.board.mask(Some(disc))) {
+                hash ^= keys[index];
+            }
+        }
+
+        hash
+    }
+
+    /// Counts the positions reachable in exactly `depth` plays. See [Game::perft()].
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let moves = self.move_mask(self.turn);
+
+        if moves == 0 {
+            if self.move_mask(self.turn.opposite()) == 0 {
+                return 1; // game over
+            }
+
+            let mut passed = *self;
+            passed.pass();
+            return passed.perft(depth - 1);
+        }
+
+        // every move leads to exactly one position, so there's no need to make them
+        if depth == 1 {
+            return moves.count_ones() as u64;
+        }
+
+        squares(moves)
+            .map(|index| {
+                let mut child = *self;
+                child.play(index);
+                child.perft(depth - 1)
+            })
+            .sum()
+    }
+}
+
+impl From<&Game> for State {
+    fn from(game: &Game) -> Self {
+        Self {
+            board: game.board,
+            turn: game.turn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{parse_transcript, Position};
+
+    fn index(square: &str) -> usize {
+        square.parse::<Position>().unwrap().into()
+    }
+
+    #[test]
+    fn test_opening_moves() {
+        let state = State::new();
+        let expected: Vec<usize> = ["d3", "c4", "f5", "e6"].map(index).to_vec();
+        let mut moves = state.moves();
+        moves.sort_unstable();
+        assert_eq!(*moves, expected);
+        assert_eq!(state.move_mask(Disc::Light).count_ones(), 4);
+
+        // d3 flips d4, and nothing else
+        assert_eq!(state.flips(index("d3")), 1 << index("d4"));
+        assert_eq!(state.flips(index("a1")), 0);
+        assert_eq!(state.flips(index("d4")), 0);
+    }
+
+    #[test]
+    fn test_no_wrapping() {
+        // light on h1 and dark on a2 are next to each other by index, but not on the board
+        let mut board = Board::default();
+        board.set(index("g1"), Disc::Dark);
+        board.set(index("h1"), Disc::Light);
+        board.set(index("a2"), Disc::Light);
+        board.set(index("b2"), Disc::Dark);
+        assert_eq!(
+            moves(board.mask(Some(Disc::Dark)), board.mask(Some(Disc::Light))),
+            0
+        );
+    }
+
+    #[test]
+    fn test_play_matches_game() {
+        let plays = parse_transcript("f5,d6,c3,d3,c4,f4,f6,f3,e6,e7").unwrap();
+        let mut game = Game::new();
+        let mut state = State::new();
+        for play in &plays {
+            game.apply(play).unwrap();
+            let flipped = state.play(play.to_string().parse::<Position>().unwrap().into());
+            assert_ne!(flipped, 0);
+            assert_eq!(state, State::from(&game));
+        }
+        assert_eq!(state.board.count(Some(Disc::Dark)), game.dark);
+    }
+
+    #[test]
+    fn test_perft() {
+        for (depth, &count) in crate::PERFT.iter().enumerate().take(7) {
+            assert_eq!(State::new().perft(depth), count);
+        }
+    }
+
+    #[test]
+    fn test_zobrist() {
+        let opening = State::new();
+        let mut passed = opening;
+        passed.pass();
+        assert_ne!(opening.zobrist(), passed.zobrist());
+
+        // every position five plays in: transpositions hash the same, and nothing else does
+        let mut frontier = vec![opening];
+        let mut paths = 0;
+        for _ in 0..5 {
+            frontier = frontier
+                .iter()
+                .flat_map(|state| {
+                    state
+                        .moves()
+                        .iter()
+                        .map(|&index| {
+                            let mut child = *state;
+                            child.play(index);
+                            child
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            paths = frontier.len();
+        }
+
+        let mut hashes = std::collections::HashMap::new();
+        for state in frontier {
+            assert_eq!(
+                *hashes.entry(state).or_insert(state.zobrist()),
+                state.zobrist()
+            );
+        }
+        let mut distinct: Vec<u64> = hashes.values().copied().collect();
+        distinct.sort_unstable();
+        distinct.dedup();
+        assert!(hashes.len() < paths);
+        assert_eq!(distinct.len(), hashes.len());
+    }
+
+    #[test]
+    fn test_move_list() {
+        let mut moves = MoveList::from_mask(0b1011 | 1 << 63);
+        assert_eq!(moves.len(), 4);
+        assert_eq!(*moves, [0, 1, 3, 63]);
+
+        moves.sort_by_key(|&index| std::cmp::Reverse(index));
+        assert_eq!((&moves).into_iter().next(), Some(&63));
+        assert!(MoveList::from_mask(0).is_empty());
+    }
+}
@@ -1,26 +1,21 @@
 use std::fmt::*;
 
+use crate::bitboard;
 use crate::data;
+use crate::render::Renderer;
 use crate::{Disc, Position};
 
-/// Represents the Othello game board, where each position is represented by `Option<Disc>`.
+/// Represents the Othello game board, as one bit per square for each color.
 ///
-/// All positions in the board are addressed by index within an array. To use coordinates,
-/// check out [Position] which offers convenience functions for translating between indexes
-/// and _(x, y)_ coordinates.
+/// All positions in the board are addressed by index, from 0 (a1) to 63 (h8). To use
+/// coordinates, check out [Position] which offers convenience functions for translating
+/// between indexes and _(x, y)_ coordinates. Bit _n_ of a mask is the square at index _n_.
 ///
 /// `Board` will panic on out of bounds indexes (> 63).
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Copy, Clone)]
 pub struct Board {
-    positions: [Option<Disc>; 64],
-}
-
-impl Default for Board {
-    fn default() -> Self {
-        Board {
-            positions: [None; 64],
-        }
-    }
+    dark: u64,
+    light: u64,
 }
 
 impl Board {
@@ -36,49 +31,120 @@ impl Board {
 
     /// Returns the [Disc] at a given index.
     pub fn get(&self, index: usize) -> Option<Disc> {
-        self.positions[index]
+        let bit = Self::bit(index);
+        if self.dark & bit != 0 {
+            Some(Disc::Dark)
+        } else if self.light & bit != 0 {
+            Some(Disc::Light)
+        } else {
+            None
+        }
     }
 
     /// Sets a [Disc] at the given index. Use [Game::play()](crate::Game::play()) to play a move
     /// with flipping and scoring.
     pub fn set(&mut self, index: usize, disc: Disc) {
-        self.positions[index] = Some(disc)
+        self.place(disc, Self::bit(index))
+    }
+
+    /// Sets every position in `mask` to `disc`, such as a move and the discs it flips.
+    pub fn place(&mut self, disc: Disc, mask: u64) {
+        match disc {
+            Disc::Dark => {
+                self.dark |= mask;
+                self.light &= !mask;
+            }
+            Disc::Light => {
+                self.light |= mask;
+                self.dark &= !mask;
+            }
+        }
+    }
+
+    /// Returns a mask of the positions matching `disc`, with bit _n_ set for index _n_.
+    pub fn mask(&self, disc: Option<Disc>) -> u64 {
+        match disc {
+            Some(Disc::Dark) => self.dark,
+            Some(Disc::Light) => self.light,
+            None => !(self.dark | self.light),
+        }
+    }
+
+    /// Returns the number of positions matching `disc`.
+    pub fn count(&self, disc: Option<Disc>) -> usize {
+        self.mask(disc).count_ones() as usize
     }
 
     /// Returns an `Iterator` over the indexes for all positions matching `disc`
     pub fn indexes_of(&self, disc: Option<Disc>) -> impl Iterator<Item = usize> + '_ {
-        self.positions
-            .iter()
-            .enumerate()
-            .filter(move |(_, p)| **p == disc)
-            .map(|(idx, _)| idx)
+        bitboard::squares(self.mask(disc))
+    }
+
+    /// Parses a 64 character position string, as produced by [Board::to_position_string()].
+    /// Dark discs may be written as `X` or `*`, light discs as `O`, and empty positions as
+    /// `-` or `.`; whitespace is ignored.
+    pub fn from_position_string(input: &str) -> std::result::Result<Self, String> {
+        let mut board = Board::default();
+        let mut index = 0;
+
+        for c in input.chars().filter(|c| !c.is_whitespace()) {
+            if index > 63 {
+                return Err(format!("Position has more than 64 squares: {}", input));
+            }
+
+            match c {
+                'X' | 'x' | '*' => board.set(index, Disc::Dark),
+                'O' | 'o' => board.set(index, Disc::Light),
+                '-' | '.' => {}
+                _ => return Err(format!("Invalid square '{}' in position", c)),
+            }
+
+            index += 1;
+        }
+
+        if index != 64 {
+            return Err(format!("Position has {} squares, expected 64", index));
+        }
+
+        Ok(board)
+    }
+
+    /// Returns the board as a 64 character string, row by row from a1 to h8, with `X` for dark
+    /// discs, `O` for light discs, and `-` for empty positions.
+    pub fn to_position_string(&self) -> String {
+        (0..64)
+            .map(|index| match self.get(index) {
+                Some(Disc::Dark) => 'X',
+                Some(Disc::Light) => 'O',
+                None => '-',
+            })
+            .collect()
     }
 
-    /// Returns the lines of play for any given position. This data is pre-generated as
-    /// static data in `src/data.rs` by `src/bin/desdata.rs`
+    /// Returns the lines of play for any given position. This data is generated at compile
+    /// time by `build.rs`, from [Position::generate_lines()].
     pub fn lines_for(&self, index: usize) -> &'static [&'static [usize]] {
         data::POSITION_INDEX_LINES[index]
     }
+
+    fn bit(index: usize) -> u64 {
+        assert!(index < 64, "Board index out of bounds: {}", index);
+        1 << index
+    }
+}
+
+impl Board {
+    /// Renders the board like its `Display` implementation, but with a `*` on each empty
+    /// position in `markers` (such as the valid moves for a player). See
+    /// [Renderer](crate::render::Renderer) for other themes and layouts.
+    pub fn display_with_markers(&self, markers: &[usize]) -> String {
+        Renderer::default().board(self, markers)
+    }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let mut output = String::new();
-        output += "  a b c d e f g h\n";
-        for row in 0..=7 {
-            let index = row * 8;
-            let row_characters: Vec<String> = self.positions[index..index + 8]
-                .iter()
-                .map(|d| match d {
-                    None => "·".to_owned(),
-                    Some(disc) => format!("{}", disc),
-                })
-                .collect();
-
-            output += &format!("{} {}\n", row + 1, row_characters.join(" "));
-        }
-
-        write!(f, "{}", output)
+        write!(f, "{}", self.display_with_markers(&[]))
     }
 }
 
@@ -98,4 +164,32 @@ mod tests {
         assert_eq!(light_indexes, 2);
         assert_eq!(dark_indexes, 2);
     }
+
+    #[test]
+    fn test_display_with_markers() {
+        let board = Board::new();
+        let marked = board.display_with_markers(&[19, 0, 27]);
+
+        // occupied positions aren't marked
+        assert_eq!(marked.lines().nth(1), Some("1 * · · · · · · ·"));
+        assert_eq!(marked.lines().nth(3), Some("3 · · · * · · · ·"));
+        assert_eq!(marked.lines().nth(4), Some("4 · · · ● ○ · · ·"));
+        assert_eq!(board.display_with_markers(&[]), format!("{}", board));
+    }
+
+    #[test]
+    fn test_position_string_round_trip() {
+        let board = Board::new();
+        let position = board.to_position_string();
+
+        assert_eq!(
+            position,
+            "---------------------------OX------XO---------------------------"
+        );
+        assert_eq!(Board::from_position_string(&position).unwrap(), board);
+
+        assert!(Board::from_position_string("XO").is_err());
+        assert!(Board::from_position_string(&position.replace('-', "?")).is_err());
+        assert!(Board::from_position_string(&format!("{}-", position)).is_err());
+    }
 }
@@ -1,551 +1,37 @@
-// GENERATED BY bin/data; do not modify by hand!
-pub const POSITION_INDEX_LINES: &[&[&[usize]]] = &[
-    &[
-        &[0, 1, 2, 3, 4, 5, 6, 7],
-        &[0, 9, 18, 27, 36, 45, 54, 63],
-        &[0, 8, 16, 24, 32, 40, 48, 56],
-    ],
-    &[
-        &[1, 2, 3, 4, 5, 6, 7],
-        &[1, 10, 19, 28, 37, 46, 55],
-        &[1, 9, 17, 25, 33, 41, 49, 57],
-        &[1, 8],
-        &[1, 0],
-    ],
-    &[
-        &[2, 3, 4, 5, 6, 7],
-        &[2, 11, 20, 29, 38, 47],
-        &[2, 10, 18, 26, 34, 42, 50, 58],
-        &[2, 9, 16],
-        &[2, 1, 0],
-    ],
-    &[
-        &[3, 4, 5, 6, 7],
-        &[3, 12, 21, 30, 39],
-        &[3, 11, 19, 27, 35, 43, 51, 59],
-        &[3, 10, 17, 24],
-        &[3, 2, 1, 0],
-    ],
-    &[
-        &[4, 5, 6, 7],
-        &[4, 13, 22, 31],
-        &[4, 12, 20, 28, 36, 44, 52, 60],
-        &[4, 11, 18, 25, 32],
-        &[4, 3, 2, 1, 0],
-    ],
-    &[
-        &[5, 6, 7],
-        &[5, 14, 23],
-        &[5, 13, 21, 29, 37, 45, 53, 61],
-        &[5, 12, 19, 26, 33, 40],
-        &[5, 4, 3, 2, 1, 0],
-    ],
-    &[
-        &[6, 7],
-        &[6, 15],
-        &[6, 14, 22, 30, 38, 46, 54, 62],
-        &[6, 13, 20, 27, 34, 41, 48],
-        &[6, 5, 4, 3, 2, 1, 0],
-    ],
-    &[
-        &[7, 15, 23, 31, 39, 47, 55, 63],
-        &[7, 14, 21, 28, 35, 42, 49, 56],
-        &[7, 6, 5, 4, 3, 2, 1, 0],
-    ],
-    &[
-        &[8, 0],
-        &[8, 1],
-        &[8, 9, 10, 11, 12, 13, 14, 15],
-        &[8, 17, 26, 35, 44, 53, 62],
-        &[8, 16, 24, 32, 40, 48, 56],
-    ],
-    &[
-        &[9, 1],
-        &[9, 2],
-        &[9, 10, 11, 12, 13, 14, 15],
-        &[9, 18, 27, 36, 45, 54, 63],
-        &[9, 17, 25, 33, 41, 49, 57],
-        &[9, 16],
-        &[9, 8],
-        &[9, 0],
-    ],
-    &[
-        &[10, 2],
-        &[10, 3],
-        &[10, 11, 12, 13, 14, 15],
-        &[10, 19, 28, 37, 46, 55],
-        &[10, 18, 26, 34, 42, 50, 58],
-        &[10, 17, 24],
-        &[10, 9, 8],
-        &[10, 1],
-    ],
-    &[
-        &[11, 3],
-        &[11, 4],
-        &[11, 12, 13, 14, 15],
-        &[11, 20, 29, 38, 47],
-        &[11, 19, 27, 35, 43, 51, 59],
-        &[11, 18, 25, 32],
-        &[11, 10, 9, 8],
-        &[11, 2],
-    ],
-    &[
-        &[12, 4],
-        &[12, 5],
-        &[12, 13, 14, 15],
-        &[12, 21, 30, 39],
-        &[12, 20, 28, 36, 44, 52, 60],
-        &[12, 19, 26, 33, 40],
-        &[12, 11, 10, 9, 8],
-        &[12, 3],
-    ],
-    &[
-        &[13, 5],
-        &[13, 6],
-        &[13, 14, 15],
-        &[13, 22, 31],
-        &[13, 21, 29, 37, 45, 53, 61],
-        &[13, 20, 27, 34, 41, 48],
-        &[13, 12, 11, 10, 9, 8],
-        &[13, 4],
-    ],
-    &[
-        &[14, 6],
-        &[14, 7],
-        &[14, 15],
-        &[14, 23],
-        &[14, 22, 30, 38, 46, 54, 62],
-        &[14, 21, 28, 35, 42, 49, 56],
-        &[14, 13, 12, 11, 10, 9, 8],
-        &[14, 5],
-    ],
-    &[
-        &[15, 7],
-        &[15, 23, 31, 39, 47, 55, 63],
-        &[15, 22, 29, 36, 43, 50, 57],
-        &[15, 14, 13, 12, 11, 10, 9, 8],
-        &[15, 6],
-    ],
-    &[
-        &[16, 8, 0],
-        &[16, 9, 2],
-        &[16, 17, 18, 19, 20, 21, 22, 23],
-        &[16, 25, 34, 43, 52, 61],
-        &[16, 24, 32, 40, 48, 56],
-    ],
-    &[
-        &[17, 9, 1],
-        &[17, 10, 3],
-        &[17, 18, 19, 20, 21, 22, 23],
-        &[17, 26, 35, 44, 53, 62],
-        &[17, 25, 33, 41, 49, 57],
-        &[17, 24],
-        &[17, 16],
-        &[17, 8],
-    ],
-    &[
-        &[18, 10, 2],
-        &[18, 11, 4],
-        &[18, 19, 20, 21, 22, 23],
-        &[18, 27, 36, 45, 54, 63],
-        &[18, 26, 34, 42, 50, 58],
-        &[18, 25, 32],
-        &[18, 17, 16],
-        &[18, 9, 0],
-    ],
-    &[
-        &[19, 11, 3],
-        &[19, 12, 5],
-        &[19, 20, 21, 22, 23],
-        &[19, 28, 37, 46, 55],
-        &[19, 27, 35, 43, 51, 59],
-        &[19, 26, 33, 40],
-        &[19, 18, 17, 16],
-        &[19, 10, 1],
-    ],
-    &[
-        &[20, 12, 4],
-        &[20, 13, 6],
-        &[20, 21, 22, 23],
-        &[20, 29, 38, 47],
-        &[20, 28, 36, 44, 52, 60],
-        &[20, 27, 34, 41, 48],
-        &[20, 19, 18, 17, 16],
-        &[20, 11, 2],
-    ],
-    &[
-        &[21, 13, 5],
-        &[21, 14, 7],
-        &[21, 22, 23],
-        &[21, 30, 39],
-        &[21, 29, 37, 45, 53, 61],
-        &[21, 28, 35, 42, 49, 56],
-        &[21, 20, 19, 18, 17, 16],
-        &[21, 12, 3],
-    ],
-    &[
-        &[22, 14, 6],
-        &[22, 15],
-        &[22, 23],
-        &[22, 31],
-        &[22, 30, 38, 46, 54, 62],
-        &[22, 29, 36, 43, 50, 57],
-        &[22, 21, 20, 19, 18, 17, 16],
-        &[22, 13, 4],
-    ],
-    &[
-        &[23, 15, 7],
-        &[23, 31, 39, 47, 55, 63],
-        &[23, 30, 37, 44, 51, 58],
-        &[23, 22, 21, 20, 19, 18, 17, 16],
-        &[23, 14, 5],
-    ],
-    &[
-        &[24, 16, 8, 0],
-        &[24, 17, 10, 3],
-        &[24, 25, 26, 27, 28, 29, 30, 31],
-        &[24, 33, 42, 51, 60],
-        &[24, 32, 40, 48, 56],
-    ],
-    &[
-        &[25, 17, 9, 1],
-        &[25, 18, 11, 4],
-        &[25, 26, 27, 28, 29, 30, 31],
-        &[25, 34, 43, 52, 61],
-        &[25, 33, 41, 49, 57],
-        &[25, 32],
-        &[25, 24],
-        &[25, 16],
-    ],
-    &[
-        &[26, 18, 10, 2],
-        &[26, 19, 12, 5],
-        &[26, 27, 28, 29, 30, 31],
-        &[26, 35, 44, 53, 62],
-        &[26, 34, 42, 50, 58],
-        &[26, 33, 40],
-        &[26, 25, 24],
-        &[26, 17, 8],
-    ],
-    &[
-        &[27, 19, 11, 3],
-        &[27, 20, 13, 6],
-        &[27, 28, 29, 30, 31],
-        &[27, 36, 45, 54, 63],
-        &[27, 35, 43, 51, 59],
-        &[27, 34, 41, 48],
-        &[27, 26, 25, 24],
-        &[27, 18, 9, 0],
-    ],
-    &[
-        &[28, 20, 12, 4],
-        &[28, 21, 14, 7],
-        &[28, 29, 30, 31],
-        &[28, 37, 46, 55],
-        &[28, 36, 44, 52, 60],
-        &[28, 35, 42, 49, 56],
-        &[28, 27, 26, 25, 24],
-        &[28, 19, 10, 1],
-    ],
-    &[
-        &[29, 21, 13, 5],
-        &[29, 22, 15],
-        &[29, 30, 31],
-        &[29, 38, 47],
-        &[29, 37, 45, 53, 61],
-        &[29, 36, 43, 50, 57],
-        &[29, 28, 27, 26, 25, 24],
-        &[29, 20, 11, 2],
-    ],
-    &[
-        &[30, 22, 14, 6],
-        &[30, 23],
-        &[30, 31],
-        &[30, 39],
-        &[30, 38, 46, 54, 62],
-        &[30, 37, 44, 51, 58],
-        &[30, 29, 28, 27, 26, 25, 24],
-        &[30, 21, 12, 3],
-    ],
-    &[
-        &[31, 23, 15, 7],
-        &[31, 39, 47, 55, 63],
-        &[31, 38, 45, 52, 59],
-        &[31, 30, 29, 28, 27, 26, 25, 24],
-        &[31, 22, 13, 4],
-    ],
-    &[
-        &[32, 24, 16, 8, 0],
-        &[32, 25, 18, 11, 4],
-        &[32, 33, 34, 35, 36, 37, 38, 39],
-        &[32, 41, 50, 59],
-        &[32, 40, 48, 56],
-    ],
-    &[
-        &[33, 25, 17, 9, 1],
-        &[33, 26, 19, 12, 5],
-        &[33, 34, 35, 36, 37, 38, 39],
-        &[33, 42, 51, 60],
-        &[33, 41, 49, 57],
-        &[33, 40],
-        &[33, 32],
-        &[33, 24],
-    ],
-    &[
-        &[34, 26, 18, 10, 2],
-        &[34, 27, 20, 13, 6],
-        &[34, 35, 36, 37, 38, 39],
-        &[34, 43, 52, 61],
-        &[34, 42, 50, 58],
-        &[34, 41, 48],
-        &[34, 33, 32],
-        &[34, 25, 16],
-    ],
-    &[
-        &[35, 27, 19, 11, 3],
-        &[35, 28, 21, 14, 7],
-        &[35, 36, 37, 38, 39],
-        &[35, 44, 53, 62],
-        &[35, 43, 51, 59],
-        &[35, 42, 49, 56],
-        &[35, 34, 33, 32],
-        &[35, 26, 17, 8],
-    ],
-    &[
-        &[36, 28, 20, 12, 4],
-        &[36, 29, 22, 15],
-        &[36, 37, 38, 39],
-        &[36, 45, 54, 63],
-        &[36, 44, 52, 60],
-        &[36, 43, 50, 57],
-        &[36, 35, 34, 33, 32],
-        &[36, 27, 18, 9, 0],
-    ],
-    &[
-        &[37, 29, 21, 13, 5],
-        &[37, 30, 23],
-        &[37, 38, 39],
-        &[37, 46, 55],
-        &[37, 45, 53, 61],
-        &[37, 44, 51, 58],
-        &[37, 36, 35, 34, 33, 32],
-        &[37, 28, 19, 10, 1],
-    ],
-    &[
-        &[38, 30, 22, 14, 6],
-        &[38, 31],
-        &[38, 39],
-        &[38, 47],
-        &[38, 46, 54, 62],
-        &[38, 45, 52, 59],
-        &[38, 37, 36, 35, 34, 33, 32],
-        &[38, 29, 20, 11, 2],
-    ],
-    &[
-        &[39, 31, 23, 15, 7],
-        &[39, 47, 55, 63],
-        &[39, 46, 53, 60],
-        &[39, 38, 37, 36, 35, 34, 33, 32],
-        &[39, 30, 21, 12, 3],
-    ],
-    &[
-        &[40, 32, 24, 16, 8, 0],
-        &[40, 33, 26, 19, 12, 5],
-        &[40, 41, 42, 43, 44, 45, 46, 47],
-        &[40, 49, 58],
-        &[40, 48, 56],
-    ],
-    &[
-        &[41, 33, 25, 17, 9, 1],
-        &[41, 34, 27, 20, 13, 6],
-        &[41, 42, 43, 44, 45, 46, 47],
-        &[41, 50, 59],
-        &[41, 49, 57],
-        &[41, 48],
-        &[41, 40],
-        &[41, 32],
-    ],
-    &[
-        &[42, 34, 26, 18, 10, 2],
-        &[42, 35, 28, 21, 14, 7],
-        &[42, 43, 44, 45, 46, 47],
-        &[42, 51, 60],
-        &[42, 50, 58],
-        &[42, 49, 56],
-        &[42, 41, 40],
-        &[42, 33, 24],
-    ],
-    &[
-        &[43, 35, 27, 19, 11, 3],
-        &[43, 36, 29, 22, 15],
-        &[43, 44, 45, 46, 47],
-        &[43, 52, 61],
-        &[43, 51, 59],
-        &[43, 50, 57],
-        &[43, 42, 41, 40],
-        &[43, 34, 25, 16],
-    ],
-    &[
-        &[44, 36, 28, 20, 12, 4],
-        &[44, 37, 30, 23],
-        &[44, 45, 46, 47],
-        &[44, 53, 62],
-        &[44, 52, 60],
-        &[44, 51, 58],
-        &[44, 43, 42, 41, 40],
-        &[44, 35, 26, 17, 8],
-    ],
-    &[
-        &[45, 37, 29, 21, 13, 5],
-        &[45, 38, 31],
-        &[45, 46, 47],
-        &[45, 54, 63],
-        &[45, 53, 61],
-        &[45, 52, 59],
-        &[45, 44, 43, 42, 41, 40],
-        &[45, 36, 27, 18, 9, 0],
-    ],
-    &[
-        &[46, 38, 30, 22, 14, 6],
-        &[46, 39],
-        &[46, 47],
-        &[46, 55],
-        &[46, 54, 62],
-        &[46, 53, 60],
-        &[46, 45, 44, 43, 42, 41, 40],
-        &[46, 37, 28, 19, 10, 1],
-    ],
-    &[
-        &[47, 39, 31, 23, 15, 7],
-        &[47, 55, 63],
-        &[47, 54, 61],
-        &[47, 46, 45, 44, 43, 42, 41, 40],
-        &[47, 38, 29, 20, 11, 2],
-    ],
-    &[
-        &[48, 40, 32, 24, 16, 8, 0],
-        &[48, 41, 34, 27, 20, 13, 6],
-        &[48, 49, 50, 51, 52, 53, 54, 55],
-        &[48, 57],
-        &[48, 56],
-    ],
-    &[
-        &[49, 41, 33, 25, 17, 9, 1],
-        &[49, 42, 35, 28, 21, 14, 7],
-        &[49, 50, 51, 52, 53, 54, 55],
-        &[49, 58],
-        &[49, 57],
-        &[49, 56],
-        &[49, 48],
-        &[49, 40],
-    ],
-    &[
-        &[50, 42, 34, 26, 18, 10, 2],
-        &[50, 43, 36, 29, 22, 15],
-        &[50, 51, 52, 53, 54, 55],
-        &[50, 59],
-        &[50, 58],
-        &[50, 57],
-        &[50, 49, 48],
-        &[50, 41, 32],
-    ],
-    &[
-        &[51, 43, 35, 27, 19, 11, 3],
-        &[51, 44, 37, 30, 23],
-        &[51, 52, 53, 54, 55],
-        &[51, 60],
-        &[51, 59],
-        &[51, 58],
-        &[51, 50, 49, 48],
-        &[51, 42, 33, 24],
-    ],
-    &[
-        &[52, 44, 36, 28, 20, 12, 4],
-        &[52, 45, 38, 31],
-        &[52, 53, 54, 55],
-        &[52, 61],
-        &[52, 60],
-        &[52, 59],
-        &[52, 51, 50, 49, 48],
-        &[52, 43, 34, 25, 16],
-    ],
-    &[
-        &[53, 45, 37, 29, 21, 13, 5],
-        &[53, 46, 39],
-        &[53, 54, 55],
-        &[53, 62],
-        &[53, 61],
-        &[53, 60],
-        &[53, 52, 51, 50, 49, 48],
-        &[53, 44, 35, 26, 17, 8],
-    ],
-    &[
-        &[54, 46, 38, 30, 22, 14, 6],
-        &[54, 47],
-        &[54, 55],
-        &[54, 63],
-        &[54, 62],
-        &[54, 61],
-        &[54, 53, 52, 51, 50, 49, 48],
-        &[54, 45, 36, 27, 18, 9, 0],
-    ],
-    &[
-        &[55, 47, 39, 31, 23, 15, 7],
-        &[55, 63],
-        &[55, 62],
-        &[55, 54, 53, 52, 51, 50, 49, 48],
-        &[55, 46, 37, 28, 19, 10, 1],
-    ],
-    &[
-        &[56, 48, 40, 32, 24, 16, 8, 0],
-        &[56, 49, 42, 35, 28, 21, 14, 7],
-        &[56, 57, 58, 59, 60, 61, 62, 63],
-    ],
-    &[
-        &[57, 49, 41, 33, 25, 17, 9, 1],
-        &[57, 50, 43, 36, 29, 22, 15],
-        &[57, 58, 59, 60, 61, 62, 63],
-        &[57, 56],
-        &[57, 48],
-    ],
-    &[
-        &[58, 50, 42, 34, 26, 18, 10, 2],
-        &[58, 51, 44, 37, 30, 23],
-        &[58, 59, 60, 61, 62, 63],
-        &[58, 57, 56],
-        &[58, 49, 40],
-    ],
-    &[
-        &[59, 51, 43, 35, 27, 19, 11, 3],
-        &[59, 52, 45, 38, 31],
-        &[59, 60, 61, 62, 63],
-        &[59, 58, 57, 56],
-        &[59, 50, 41, 32],
-    ],
-    &[
-        &[60, 52, 44, 36, 28, 20, 12, 4],
-        &[60, 53, 46, 39],
-        &[60, 61, 62, 63],
-        &[60, 59, 58, 57, 56],
-        &[60, 51, 42, 33, 24],
-    ],
-    &[
-        &[61, 53, 45, 37, 29, 21, 13, 5],
-        &[61, 54, 47],
-        &[61, 62, 63],
-        &[61, 60, 59, 58, 57, 56],
-        &[61, 52, 43, 34, 25, 16],
-    ],
-    &[
-        &[62, 54, 46, 38, 30, 22, 14, 6],
-        &[62, 55],
-        &[62, 63],
-        &[62, 61, 60, 59, 58, 57, 56],
-        &[62, 53, 44, 35, 26, 17, 8],
-    ],
-    &[
-        &[63, 55, 47, 39, 31, 23, 15, 7],
-        &[63, 62, 61, 60, 59, 58, 57, 56],
-        &[63, 54, 45, 36, 27, 18, 9, 0],
-    ],
-];
+// The tables are generated at compile time by `build.rs`: the lines of play from
+// `Position::generate_lines()`, and the Zobrist keys.
+include!(concat!(env!("OUT_DIR"), "/data.rs"));
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::position::tests::VERIFIED_LINES;
+    use crate::Position;
+
+    #[test]
+    fn test_position_index_lines() {
+        assert_eq!(POSITION_INDEX_LINES.len(), 64);
+
+        for (index, lines) in POSITION_INDEX_LINES.iter().enumerate() {
+            assert_eq!(lines.to_vec(), Position::new(index).generate_lines());
+        }
+
+        // the directions with nowhere to go are left out
+        for (index, verified) in VERIFIED_LINES {
+            let expected: Vec<&[usize]> = verified.into_iter().filter(|l| l.len() > 1).collect();
+            assert_eq!(POSITION_INDEX_LINES[index], expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn test_zobrist_keys() {
+        let mut keys: Vec<u64> = ZOBRIST_DISCS.iter().flatten().copied().collect();
+        keys.push(ZOBRIST_DARK_TO_MOVE);
+        keys.sort_unstable();
+        keys.dedup();
+
+        assert_eq!(keys.len(), 129);
+        assert!(keys.iter().all(|&key| key.count_ones() > 8));
+    }
+}
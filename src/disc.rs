@@ -1,9 +1,11 @@
+use crate::render::Renderer;
 use std::fmt::*;
 
 /// A light or dark game disc
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Disc {
     Light,
+    #[default]
     Dark,
 }
 
@@ -18,19 +20,7 @@ impl Disc {
 }
 
 impl Display for Disc {
-    // TODO: This only works well for people using dark terminal themes
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let representation = match self {
-            Disc::Dark => "○",
-            Disc::Light => "●",
-        };
-
-        write!(f, "{}", representation)
-    }
-}
-
-impl Default for Disc {
-    fn default() -> Self {
-        Disc::Dark
+        write!(f, "{}", Renderer::default().disc(*self))
     }
 }
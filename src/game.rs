@@ -1,5 +1,8 @@
+use crate::bitboard::{self, State};
+use crate::render::Renderer;
 use crate::{Board, Disc, Position};
 use std::fmt::*;
+use std::str::FromStr;
 
 /// Represents individual plays within the transcript of a game
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -17,6 +20,50 @@ impl Display for Play {
     }
 }
 
+impl FromStr for Play {
+    type Err = String;
+
+    /// Parses a single play, either a coordinate like `d3` or `p` for a pass.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "p" | "pass" => Ok(Play::Pass),
+            other => Ok(Play::Move(other.parse()?)),
+        }
+    }
+}
+
+/// Parses a transcript of plays, either separated by commas or whitespace (`d3,c5,p`) or run
+/// together the way `desvs` prints them (`d3c5p`).
+pub fn parse_transcript(input: &str) -> std::result::Result<Vec<Play>, String> {
+    let compact: Vec<char> = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+
+    let mut plays = vec![];
+    let mut index = 0;
+    while index < compact.len() {
+        if compact[index].eq_ignore_ascii_case(&'p') {
+            plays.push(Play::Pass);
+            index += 1;
+        } else {
+            let coordinate: String = compact[index..compact.len().min(index + 2)]
+                .iter()
+                .collect();
+            plays.push(Play::Move(coordinate.parse()?));
+            index += 2;
+        }
+    }
+
+    Ok(plays)
+}
+
+/// The published perft counts from the opening position, for depths 0 through 10: how many
+/// positions can be reached in exactly that many plays. See [Game::perft].
+pub const PERFT: [u64; 11] = [
+    1, 4, 12, 56, 244, 1396, 8200, 55092, 390216, 3005288, 24571284,
+];
+
 /// Represents the current state of a game in progress. The board positions are indexed from 0 to 63.
 /// See [Board] for more details on how this works.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
@@ -71,6 +118,24 @@ impl Game {
         Some(game)
     }
 
+    /// Sets up a game from an arbitrary board, with `turn` to play next. The transcript starts
+    /// out empty, so it only covers the plays made from this position on.
+    pub fn from_position(board: Board, turn: Disc) -> Self {
+        let mut game = Game {
+            turn,
+            dark: board.indexes_of(Some(Disc::Dark)).count(),
+            light: board.indexes_of(Some(Disc::Light)).count(),
+            empty: board.indexes_of(None).count(),
+            board,
+            transcript: Vec::with_capacity(64),
+            is_complete: false,
+        };
+
+        game.validate_completion();
+
+        game
+    }
+
     /// Determines if a position is a valid move for the current player. Returns the index if so.
     pub fn validate_move(&self, player: Disc, index: usize) -> Option<usize> {
         if self.can_move(player, index) {
@@ -82,76 +147,28 @@ impl Game {
 
     /// Returns an `Iterator` with all of the valid moves for the current player.
     pub fn valid_moves(&self, player: Disc) -> impl Iterator<Item = usize> + '_ {
-        self.board
-            .indexes_of(None)
-            .filter(move |index| self.can_move(player, *index))
+        bitboard::squares(self.state().move_mask(player))
     }
 
     /// Returns the position of the discs that _would_ be flipped by playing at a given position.
+    /// See [State::flips()] for the same as a mask, without allocating.
     pub fn flips_for(&self, index: usize) -> Vec<usize> {
-        let player = self.turn;
-        let opposition = self.turn.opposite();
-
-        // reusable buffer for collecting lines
-        let mut line_flips = Vec::with_capacity(8);
-
-        // all flips for this particular move
-        let mut flips = Vec::with_capacity(32);
-
-        for line in self.board.lines_for(index) {
-            line_flips.clear();
-            for line_index in line.iter() {
-                // skip the position being analyzed
-                if line_index == &index {
-                    continue;
-                }
-
-                let disc = self.board.get(*line_index);
-
-                // dead end!
-                if disc == None {
-                    break;
-                }
-
-                // possible target disc to flip!
-                if disc == Some(opposition) {
-                    line_flips.push(*line_index);
-                    continue;
-                }
-
-                // we've found our own color -- if we have targets, it's a valid move!
-                if disc == Some(player) {
-                    // nothing buffered? nothing to flip. next line!
-                    if line_flips.is_empty() {
-                        break;
-                    }
-
-                    // we've buffered some line flips; add them to the over all list of flips
-                    flips.append(&mut line_flips);
-                    break;
-                }
-            }
-        }
+        bitboard::squares(self.state().flips(index)).collect()
+    }
 
-        flips
+    /// Returns the board and the player to move, without the transcript. It's cheap to copy,
+    /// for trying out moves; see [State].
+    pub fn state(&self) -> State {
+        State::from(self)
     }
 
     /// Plays a disc at the given index for the current player, flipping opposition discs
     /// and updating the score.
     pub fn play(&mut self, index: usize) {
-        // find the flips for playing that position
-        let mut flips = self.flips_for(index);
-
-        // save this for scoring updates
-        let changed = flips.len();
-
-        // add the position to discs to set
-        flips.push(index);
-
-        // iterate through the flips and set those discs to the current player
-        for p in flips.into_iter() {
-            self.board.set(p, self.turn);
-        }
+        // play it on a copy of the board and turn, and keep the result
+        let mut state = self.state();
+        let changed = state.play(index).count_ones() as usize;
+        self.board = state.board;
 
         // save the played position to the transcript
         self.transcript.push(Play::Move(Position::new(index)));
@@ -175,6 +192,27 @@ impl Game {
         self.turn = self.turn.opposite();
     }
 
+    /// Plays a move or pass for the current player, checking that it's legal first. Passing is
+    /// only allowed when the current player has no valid moves.
+    pub fn apply(&mut self, play: &Play) -> std::result::Result<(), String> {
+        match play {
+            Play::Move(position) => {
+                let index = self
+                    .validate_move(self.turn, position.into())
+                    .ok_or_else(|| format!("Invalid move: {}", position))?;
+                self.play(index);
+            }
+            Play::Pass => {
+                if self.valid_moves(self.turn).next().is_some() {
+                    return Err("Can't pass while there are valid moves".to_owned());
+                }
+                self.pass();
+            }
+        }
+
+        Ok(())
+    }
+
     /// Forfeits a turn for the current player
     pub fn pass(&mut self) {
         // if the last (opponent) play was also a pass, then the game is over.
@@ -187,66 +225,29 @@ impl Game {
         }
     }
 
-    fn validate_completion(&mut self) {
+    /// Counts the positions reachable in exactly `depth` plays, for checking move generation
+    /// against the published counts in [PERFT]. A forced pass counts as a play, and a game
+    /// that ends before `depth` counts once, as the position it ended in.
+    pub fn perft(&self, depth: usize) -> u64 {
+        self.state().perft(depth)
+    }
+
+    pub(crate) fn validate_completion(&mut self) {
         // if there are no more valid moves for either player, then the game is complete.
         self.is_complete =
             self.valid_moves(Disc::Dark).count() == 0 && self.valid_moves(Disc::Light).count() == 0
     }
 
     fn can_move(&self, player: Disc, index: usize) -> bool {
-        // basic check ... occupied?
-        if self.board.get(index).is_some() {
-            return false;
-        }
-
-        for line in self.board.lines_for(index) {
-            if self.move_from_empty(player, line).is_some() {
-                return true;
-            }
-        }
-
-        false
-    }
-
-    fn move_from_empty(&self, player: Disc, indexes: &[usize]) -> Option<usize> {
-        let mut line_started = false;
-
-        for idx in indexes {
-            // ignore the first position; it's not a target, it's where we'll be placing the piece!
-            if idx == indexes.first()? {
-                continue;
-            }
-
-            // no disc? return.
-            let disc = self.board.get(*idx)?;
-
-            // possible target disc to flip!
-            if disc == player.opposite() {
-                line_started = true;
-                continue;
-            }
-
-            // we've found our own color -- if we have targets, it's a valid move!
-            if disc == player {
-                if !line_started {
-                    return None;
-                }
-
-                return indexes.first().cloned();
-            }
-        }
-
-        None
+        let mine = self.board.mask(Some(player));
+        let theirs = self.board.mask(Some(player.opposite()));
+        bitboard::flips(mine, theirs, index) != 0
     }
 }
 
 impl Display for Game {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        writeln!(
-            f,
-            "{}\nTurn: {} Dark: {} Light: {} Empty: {}",
-            self.board, self.turn, self.dark, self.light, self.empty,
-        )
+        write!(f, "{}", Renderer::default().game(self))
     }
 }
 
@@ -254,6 +255,30 @@ impl Display for Game {
 mod tests {
 
     use super::*;
+    use crate::strategies::{Simple, Strategy};
+
+    #[test]
+    fn test_perft() {
+        let game = Game::new();
+        for (depth, &count) in PERFT.iter().enumerate().take(7) {
+            assert_eq!(game.perft(depth), count, "perft({})", depth);
+        }
+    }
+
+    #[test]
+    fn test_perft_passes_and_game_over() {
+        // dark has nothing to play after c1, so the only play is a pass
+        let transcript = parse_transcript("d3,c3,b3,b2,f5,a3,a1,c1").unwrap();
+        let game = Game::from_transcript(&transcript).unwrap();
+        assert_eq!(game.valid_moves(Disc::Dark).count(), 0);
+        assert_eq!(game.perft(1), 1);
+        assert_eq!(game.perft(2), game.valid_moves(Disc::Light).count() as u64);
+
+        // a finished game counts once, however deep
+        let mut finished = Game::new();
+        Simple {}.solve(&mut finished);
+        assert_eq!(finished.perft(4), 1);
+    }
 
     #[test]
     fn test_valid_moves() {
@@ -355,6 +380,52 @@ mod tests {
         assert_eq!(game_from_transcript, game);
     }
 
+    #[test]
+    fn test_parse_transcript() {
+        let expected = vec![
+            Play::Move(Position::from_xy(3, 2)),
+            Play::Move(Position::from_xy(2, 4)),
+            Play::Pass,
+        ];
+
+        assert_eq!(parse_transcript("d3,c5,p").unwrap(), expected);
+        assert_eq!(parse_transcript("D3 c5 P").unwrap(), expected);
+        assert_eq!(parse_transcript("d3c5p").unwrap(), expected);
+        assert_eq!(parse_transcript("").unwrap(), vec![]);
+
+        assert!(parse_transcript("d3,c").is_err());
+        assert!(parse_transcript("d3,z9").is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut game = Game::new();
+
+        assert!(game.apply(&Play::Pass).is_err());
+        assert!(game.apply(&Play::Move(Position::new(0))).is_err());
+        assert!(game.apply(&Play::Move(Position::from_xy(3, 2))).is_ok());
+
+        assert_eq!(game.turn, Disc::Light);
+        assert_eq!(game.transcript, vec![Play::Move(Position::from_xy(3, 2))]);
+    }
+
+    #[test]
+    fn test_from_position() {
+        let mut game = Game::new();
+        game.play(Position::from_xy(3, 2).into());
+
+        let position = Game::from_position(game.board, game.turn);
+
+        assert_eq!(position.board, game.board);
+        assert_eq!(position.turn, Disc::Light);
+        assert_eq!(
+            (position.dark, position.light, position.empty),
+            (game.dark, game.light, game.empty)
+        );
+        assert!(position.transcript.is_empty());
+        assert!(!position.is_complete);
+    }
+
     #[test]
     fn test_scoring() {
         let mut game = Game::new();
@@ -0,0 +1,273 @@
+//! Reading and writing games in the Generic Game Format (GGF), as used by NBoard and the GGS
+//! game servers.
+//!
+//! An Othello game in GGF looks something like this (whitespace added for clarity):
+//!
+//! ```text
+//! (;GM[Othello]PC[NBoard]PB[dark player]PW[light player]RE[?]TY[8]
+//!   BO[8 ---------------------------O*------*O--------------------------- *]
+//!   B[D3//0.01]W[C5]B[PA];)
+//! ```
+//!
+//! `BO` holds the starting board and side to move (`*` is dark, `O` is light), and each `B` or
+//! `W` tag is a move for dark or light, with an optional evaluation and time after slashes.
+//! A `]` or `\` inside a value is escaped with a backslash.
+
+use crate::{Board, Disc, Game, Play};
+use std::fmt::{self, Display, Formatter};
+
+/// The parts of a GGF game that matter for replaying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GgfGame {
+    /// The starting board
+    pub board: Board,
+    /// Which player moves first from the starting board
+    pub turn: Disc,
+    /// Every play from the starting board, in order
+    pub plays: Vec<(Disc, Play)>,
+    /// All of the other tags, such as the player names (`PB`, `PW`) and result (`RE`)
+    pub tags: Vec<(String, String)>,
+}
+
+impl GgfGame {
+    /// Parses a single GGF game.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let start = input
+            .find("(;")
+            .ok_or_else(|| "Missing '(;' at the start of the game".to_owned())?;
+        let end = input
+            .rfind(";)")
+            .ok_or_else(|| "Missing ';)' at the end of the game".to_owned())?;
+        if end < start + 2 {
+            return Err("Malformed game".to_owned());
+        }
+
+        let mut game = GgfGame {
+            board: Board::new(),
+            turn: Disc::Dark,
+            plays: vec![],
+            tags: vec![],
+        };
+
+        let mut rest = &input[start + 2..end];
+        while let Some(open) = rest.find('[') {
+            let tag = rest[..open].trim();
+            let (value, length) = parse_value(&rest[open + 1..])
+                .ok_or_else(|| format!("Unterminated value for tag {}", tag))?;
+            rest = &rest[open + 1 + length..];
+
+            match tag {
+                "BO" => {
+                    let (board, turn) = parse_board(&value)?;
+                    game.board = board;
+                    game.turn = turn;
+                }
+                "B" => game.plays.push((Disc::Dark, parse_play(&value)?)),
+                "W" => game.plays.push((Disc::Light, parse_play(&value)?)),
+                _ => game.tags.push((tag.to_owned(), value)),
+            }
+        }
+
+        Ok(game)
+    }
+
+    /// Parses every game in a file of GGF games, such as a server's game archive.
+    pub fn parse_all(input: &str) -> Result<Vec<Self>, String> {
+        input
+            .split_inclusive(";)")
+            .filter(|game| !game.trim().is_empty())
+            .enumerate()
+            .map(|(n, game)| Self::parse(game).map_err(|e| format!("game {}: {}", n + 1, e)))
+            .collect()
+    }
+
+    /// Records a game played from the opening position. Tags can be added afterwards.
+    pub fn from_transcript(transcript: &[Play]) -> Result<Self, String> {
+        let mut game = Game::new();
+        let mut plays = vec![];
+
+        for play in transcript {
+            plays.push((game.turn, play.clone()));
+            game.apply(play)?;
+        }
+
+        Ok(GgfGame {
+            board: Board::new(),
+            turn: Disc::Dark,
+            plays,
+            tags: vec![("GM".to_owned(), "Othello".to_owned())],
+        })
+    }
+
+    /// Returns the value of a tag, such as `PB` for the dark player's name.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replays the game, checking that every play is legal and made by the right player.
+    /// Games that start from the opening position keep their full transcript.
+    pub fn to_game(&self) -> Result<Game, String> {
+        let mut game = if self.board == Board::new() && self.turn == Disc::Dark {
+            Game::new()
+        } else {
+            Game::from_position(self.board, self.turn)
+        };
+
+        for (player, play) in &self.plays {
+            if *player != game.turn {
+                return Err(format!("Play {} made out of turn", play));
+            }
+            game.apply(play)?;
+        }
+
+        // games can end without the final passes being recorded
+        game.validate_completion();
+
+        Ok(game)
+    }
+}
+
+impl Display for GgfGame {
+    /// Writes the game on a single line, tags first, as GGF archives usually are.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "(;")?;
+        for (tag, value) in &self.tags {
+            // a closing bracket in a value is escaped with a backslash, as are backslashes
+            let escaped = value.replace('\\', "\\\\").replace(']', "\\]");
+            write!(f, "{}[{}]", tag, escaped)?;
+        }
+
+        let board = self.board.to_position_string().replace('X', "*");
+        let turn = match self.turn {
+            Disc::Dark => '*',
+            Disc::Light => 'O',
+        };
+        write!(f, "BO[8 {} {}]", board, turn)?;
+
+        for (player, play) in &self.plays {
+            let tag = match player {
+                Disc::Dark => "B",
+                Disc::Light => "W",
+            };
+            match play {
+                Play::Pass => write!(f, "{}[PA]", tag)?,
+                Play::Move(position) => {
+                    write!(f, "{}[{}]", tag, position.to_string().to_ascii_uppercase())?
+                }
+            }
+        }
+
+        write!(f, ";)")
+    }
+}
+
+/// Reads a tag's value from just after its opening bracket, undoing any backslash escapes.
+/// Returns the value and the length of the input it took up, including the closing bracket.
+fn parse_value(input: &str) -> Option<(String, usize)> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+
+    while let Some((n, c)) = chars.next() {
+        match c {
+            ']' => return Some((value, n + 1)),
+            '\\' => value.push(chars.next()?.1),
+            c => value.push(c),
+        }
+    }
+
+    None
+}
+
+fn parse_board(value: &str) -> Result<(Board, Disc), String> {
+    let mut words: Vec<&str> = value.split_whitespace().collect();
+
+    if words.first() != Some(&"8") {
+        return Err(format!("Only 8x8 boards are supported: {}", value));
+    }
+
+    let turn = match words.pop() {
+        Some("*") => Disc::Dark,
+        Some("O") => Disc::Light,
+        _ => return Err(format!("Missing side to move: {}", value)),
+    };
+
+    let board = Board::from_position_string(&words[1..].concat())?;
+
+    Ok((board, turn))
+}
+
+fn parse_play(value: &str) -> Result<Play, String> {
+    // moves may carry an evaluation and time, eg: "D3/1.25/0.4"
+    let play = value.split('/').next().unwrap_or("").trim();
+
+    match play.to_ascii_uppercase().as_str() {
+        "PA" | "PASS" => Ok(Play::Pass),
+        _ => play.parse(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Position;
+
+    const GAME: &str = "(;GM[Othello]PC[NBoard]PB[desdemona]PW[someone]RE[?]TY[8]\
+        BO[8 ---------------------------O*------*O--------------------------- *]\
+        B[D3//0.01]W[C5/-1.5]B[f6];)";
+
+    #[test]
+    fn test_parse() {
+        let ggf = GgfGame::parse(GAME).unwrap();
+
+        assert_eq!(ggf.board, Board::new());
+        assert_eq!(ggf.turn, Disc::Dark);
+        assert_eq!(ggf.tag("PB"), Some("desdemona"));
+        assert_eq!(
+            ggf.plays,
+            vec![
+                (Disc::Dark, Play::Move(Position::from_xy(3, 2))),
+                (Disc::Light, Play::Move(Position::from_xy(2, 4))),
+                (Disc::Dark, Play::Move(Position::from_xy(5, 5))),
+            ]
+        );
+
+        let game = ggf.to_game().unwrap();
+        assert_eq!(game.transcript.len(), 3);
+        assert_eq!(game.turn, Disc::Light);
+    }
+
+    #[test]
+    fn test_write() {
+        let transcript = crate::parse_transcript("d3,c5,f6").unwrap();
+        let mut ggf = GgfGame::from_transcript(&transcript).unwrap();
+        ggf.tags.push(("PB".to_owned(), "alpha[beta]".to_owned()));
+        ggf.tags.push(("PW".to_owned(), "back\\slash".to_owned()));
+
+        let written = ggf.to_string();
+        assert!(written.starts_with("(;GM[Othello]PB[alpha[beta\\]]PW[back\\\\slash]BO[8 "));
+        assert!(written.ends_with("B[D3]W[C5]B[F6];)"));
+
+        let archive = format!("{}\n{}\n", written, GAME);
+        let games = GgfGame::parse_all(&archive).unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0], ggf);
+        assert_eq!(games[0].to_game().unwrap().transcript, transcript);
+        assert!(GgfGame::from_transcript(&crate::parse_transcript("a1").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(GgfGame::parse("GM[Othello]").is_err());
+        assert!(GgfGame::parse("(;BO[10 --- *];)").is_err());
+
+        // out of turn, and illegal moves
+        let ggf = GgfGame::parse(&GAME.replace("W[C5/-1.5]", "B[C5]")).unwrap();
+        assert!(ggf.to_game().is_err());
+        let ggf = GgfGame::parse(&GAME.replace("D3", "A1")).unwrap();
+        assert!(ggf.to_game().is_err());
+    }
+}
@@ -32,6 +32,12 @@
 //! println!("Transcript: {}", plays.join(","));
 //! ```
 
+/// Judging the moves of a finished game
+pub mod analysis;
+
+/// Allocation-free move generation on bitboards
+pub mod bitboard;
+
 /// The 8x8 game board
 pub mod board;
 
@@ -44,13 +50,36 @@ pub mod disc;
 /// Game state and rules
 pub mod game;
 
+/// Reading and writing games in the Generic Game Format
+pub mod ggf;
+
+/// The NBoard engine protocol
+pub mod nboard;
+
+/// Playing between two machines over TCP
+pub mod network;
+
 /// Utilities for calculating relative and absolute board positions
 pub mod position;
 
+/// Line based protocol for talking to out-of-process engines
+pub mod protocol;
+
+/// Endgame puzzles with a single winning move
+pub mod puzzle;
+
+/// Drawing boards and games as text, with selectable themes
+pub mod render;
+
+/// Serde support for the core types
+#[cfg(feature = "serde")]
+mod serialize;
+
 /// Gameplay strategies
 pub mod strategies;
 
+pub use bitboard::{MoveList, State};
 pub use board::Board;
 pub use disc::Disc;
-pub use game::{Game, Play};
+pub use game::{parse_transcript, Game, Play, PERFT};
 pub use position::Position;
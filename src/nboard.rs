@@ -0,0 +1,172 @@
+//! The engine side of the [NBoard](http://www.orbanova.com/nboard/) protocol, so Desdemona's
+//! strategies can be loaded into the NBoard GUI (and anything else that speaks it).
+//!
+//! Only the parts of the protocol that make sense for Desdemona's strategies are supported:
+//! `nboard`, `set depth`, `set game`, `move`, `go`, `hint`, `ping` and `learn`. Anything else is
+//! quietly ignored, as the protocol asks.
+//!
+//! Scores are reported exactly as the strategy's [ScoredPlay](crate::strategies::ScoredPlay)s
+//! give them, so their scale depends on the strategy. While searching for a move, a
+//! `nodestats` line is sent with each of the search's progress reports.
+
+use crate::ggf::GgfGame;
+use crate::strategies::{Search, Strategy};
+use crate::{Game, Play, Position};
+use std::sync::Mutex;
+
+/// Keeps track of the game NBoard has set up, and answers its commands with a [Strategy].
+pub struct NBoardSession {
+    strategy: Box<dyn Strategy>,
+    game: Game,
+    depth: usize,
+}
+
+impl NBoardSession {
+    pub fn new(strategy: Box<dyn Strategy>) -> Self {
+        Self {
+            strategy,
+            game: Game::new(),
+            depth: 1,
+        }
+    }
+
+    /// The game as NBoard has set it up so far.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Handles one line from NBoard, returning the lines to send back.
+    pub fn handle(&mut self, line: &str) -> Vec<String> {
+        let streamed = Mutex::new(vec![]);
+        let mut output = self.handle_streaming(line, |line| streamed.lock().unwrap().push(line));
+
+        let mut all = streamed.into_inner().unwrap();
+        all.append(&mut output);
+        all
+    }
+
+    /// Like [NBoardSession::handle()], but passes the lines sent while searching to `send` as
+    /// they happen, rather than returning them with the rest once it's done.
+    pub fn handle_streaming(&mut self, line: &str, send: impl Fn(String) + Sync) -> Vec<String> {
+        let mut words = line.split_whitespace();
+
+        match (words.next(), words.next()) {
+            (Some("nboard"), _) => vec![format!(
+                "set myname {}-{}",
+                self.strategy.name(),
+                self.strategy.version()
+            )],
+            (Some("set"), Some("depth")) => {
+                if let Some(depth) = words.next().and_then(|d| d.parse().ok()) {
+                    self.depth = depth;
+                }
+                vec![]
+            }
+            (Some("set"), Some("game")) => {
+                let ggf = line.split_once("game").map(|(_, g)| g).unwrap_or("");
+                match GgfGame::parse(ggf).and_then(|ggf| ggf.to_game()) {
+                    Ok(game) => {
+                        self.game = game;
+                        vec![]
+                    }
+                    Err(e) => vec![format!("status Invalid game: {}", e)],
+                }
+            }
+            (Some("move"), Some(play)) => {
+                // moves may carry an evaluation and time, eg: "F5/1.25/0.4"
+                let play = play.split('/').next().unwrap_or("");
+                let play = match play.to_ascii_uppercase().as_str() {
+                    "PA" | "PASS" => Ok(Play::Pass),
+                    _ => play.parse(),
+                };
+
+                match play.and_then(|p| self.game.apply(&p)) {
+                    Ok(()) => vec![],
+                    Err(e) => vec![format!("status {}", e)],
+                }
+            }
+            (Some("go"), _) => {
+                send("status Thinking".to_owned());
+                let search = Search::new().with_info(|info| {
+                    send(format!(
+                        "nodestats {} {:.2}",
+                        info.nodes,
+                        info.elapsed.as_secs_f32()
+                    ))
+                });
+                let play = match self.strategy.search_play(&self.game, &search) {
+                    Some(index) => format_play(&Play::Move(Position::new(index))),
+                    None => format_play(&Play::Pass),
+                };
+
+                vec![
+                    format!("=== {}//{:.2}", play, search.elapsed().as_secs_f32()),
+                    "status".to_owned(),
+                ]
+            }
+            (Some("hint"), count) => {
+                let count = count.and_then(|c| c.parse().ok()).unwrap_or(1);
+
+                let mut scored = self.strategy.score_plays(&self.game);
+                scored.sort_by(|a, b| b.cmp(a));
+
+                let mut output = vec!["status Analyzing".to_owned()];
+                output.extend(scored.into_iter().take(count).map(|s| {
+                    format!(
+                        "search {} {} 0 {}",
+                        format_play(&Play::Move(Position::new(s.index))),
+                        s.score,
+                        self.depth
+                    )
+                }));
+                output.push("status".to_owned());
+                output
+            }
+            (Some("ping"), n) => vec![format!("pong {}", n.unwrap_or("0"))],
+            (Some("learn"), _) => vec!["learned".to_owned()],
+            _ => vec![],
+        }
+    }
+}
+
+/// NBoard writes moves in upper case, and passes as `PA`.
+fn format_play(play: &Play) -> String {
+    match play {
+        Play::Move(position) => position.to_string().to_ascii_uppercase(),
+        Play::Pass => "PA".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::strategies::Maximize;
+    use crate::Disc;
+
+    #[test]
+    fn test_session() {
+        let mut session = NBoardSession::new(Box::new(Maximize {}));
+
+        assert_eq!(session.handle("nboard 2"), vec!["set myname maximize-0.1"]);
+        assert_eq!(session.handle("ping 7"), vec!["pong 7"]);
+
+        let ggf = "(;GM[Othello]PC[NBoard]TY[8]\
+            BO[8 ---------------------------O*------*O--------------------------- *]B[D3];)";
+        assert!(session.handle(&format!("set game {}", ggf)).is_empty());
+        assert_eq!(session.game().turn, Disc::Light);
+
+        assert!(session.handle("move C5/0.5/1.2").is_empty());
+        assert_eq!(session.game().transcript.len(), 2);
+        assert_eq!(session.handle("move A1"), vec!["status Invalid move: a1"]);
+
+        let hints = session.handle("hint 2");
+        assert_eq!(hints.len(), 4);
+        assert!(hints[1].starts_with("search "));
+
+        let output = session.handle("go");
+        assert_eq!(output[0], "status Thinking");
+        assert!(output[1].starts_with("nodestats "));
+        assert!(output[2].starts_with("=== "));
+    }
+}
@@ -0,0 +1,278 @@
+//! Playing a game between two machines over TCP.
+//!
+//! One side hosts, listening on a port, and the other joins. Each side sends one [Message] per
+//! line:
+//!
+//! | Message                     | Meaning                                                  |
+//! |-----------------------------|----------------------------------------------------------|
+//! | `game <dark/light> d3,c5`   | sent by the host when someone joins (or rejoins): the color the joining player has, and the game so far |
+//! | `play d3`                   | a move, or `play p` for a pass                           |
+//! | `chat <text>`               | a line of chat                                           |
+//! | `resign`                    | the sender gives up                                      |
+//! | `error <message>`           | the last message was rejected                            |
+//!
+//! Both ends keep their own copy of the game in a [NetworkGame], which checks every play (local
+//! or remote) with [Game::apply] before it's made, so moves go through [Game::validate_move] and
+//! passes are only allowed when there's no move to make.
+
+use crate::protocol::split_line;
+use crate::{parse_transcript, Disc, Game, Play};
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+
+/// The port used when none is given.
+pub const DEFAULT_PORT: u16 = 7474;
+
+/// Messages exchanged between the two players.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Game { color: Disc, transcript: Vec<Play> },
+    Play(Play),
+    Chat(String),
+    Resign,
+    Error(String),
+}
+
+impl Display for Message {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Message::Game { color, transcript } => {
+                let plays: Vec<String> = transcript.iter().map(|p| p.to_string()).collect();
+                let color = format!("{:?}", color).to_lowercase();
+                write!(f, "game {} {}", color, plays.join(","))
+            }
+            Message::Play(play) => write!(f, "play {}", play),
+            Message::Chat(text) => write!(f, "chat {}", text),
+            Message::Resign => write!(f, "resign"),
+            Message::Error(message) => write!(f, "error {}", message),
+        }
+    }
+}
+
+impl FromStr for Message {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (keyword, rest) = split_line(line);
+
+        match keyword {
+            "game" => {
+                let (color, transcript) = split_line(rest);
+                let color = match color {
+                    "dark" => Disc::Dark,
+                    "light" => Disc::Light,
+                    _ => return Err(format!("Invalid color: {}", color)),
+                };
+                Ok(Message::Game {
+                    color,
+                    transcript: parse_transcript(transcript)?,
+                })
+            }
+            "play" => Ok(Message::Play(rest.parse()?)),
+            "chat" => Ok(Message::Chat(rest.to_owned())),
+            "resign" => Ok(Message::Resign),
+            "error" => Ok(Message::Error(rest.to_owned())),
+            _ => Err(format!("Unknown message: {}", line.trim())),
+        }
+    }
+}
+
+/// A connection to the other player.
+pub struct Peer {
+    stream: TcpStream,
+}
+
+impl Peer {
+    /// Waits for the other player to join.
+    pub fn accept(listener: &TcpListener) -> io::Result<Self> {
+        let (stream, _) = listener.accept()?;
+        Ok(Self { stream })
+    }
+
+    /// Joins a hosted game.
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        Ok(Self {
+            stream: TcpStream::connect(address)?,
+        })
+    }
+
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        writeln!(self.stream, "{}", message)?;
+        self.stream.flush()
+    }
+
+    /// The messages from the other player, until they disconnect. Reads from a clone of the
+    /// connection, so it can be handed to another thread while this one keeps sending.
+    pub fn incoming(&self) -> io::Result<impl Iterator<Item = Result<Message, String>>> {
+        let reader = BufReader::new(self.stream.try_clone()?);
+        Ok(reader
+            .lines()
+            .map_while(Result::ok)
+            .map(|line| line.parse()))
+    }
+}
+
+/// One end's copy of a network game: the game itself, and which color is played here.
+#[derive(Debug, Clone)]
+pub struct NetworkGame {
+    pub game: Game,
+    pub local: Disc,
+    resigned: Option<Disc>,
+}
+
+impl NetworkGame {
+    pub fn new(local: Disc) -> Self {
+        Self {
+            game: Game::new(),
+            local,
+            resigned: None,
+        }
+    }
+
+    /// Sets up the joining player's side from the host's `game` message.
+    pub fn from_message(message: &Message) -> Result<Self, String> {
+        let Message::Game { color, transcript } = message else {
+            return Err(format!("Expected a game, not: {}", message));
+        };
+
+        let mut network_game = Self::new(*color);
+        for play in transcript {
+            network_game.game.apply(play)?;
+        }
+
+        Ok(network_game)
+    }
+
+    /// Tells a joining player about the game so far, and which color they have.
+    pub fn welcome(&self) -> Message {
+        Message::Game {
+            color: self.local.opposite(),
+            transcript: self.game.transcript.clone(),
+        }
+    }
+
+    pub fn is_local_turn(&self) -> bool {
+        self.game.turn == self.local && !self.is_over()
+    }
+
+    /// Whether the game is complete, or one side has resigned.
+    pub fn is_over(&self) -> bool {
+        self.game.is_complete || self.resigned.is_some()
+    }
+
+    /// Who resigned, if anyone did.
+    pub fn resigned(&self) -> Option<Disc> {
+        self.resigned
+    }
+
+    /// Makes a play for this end, returning the message to send to the other player.
+    pub fn play_local(&mut self, play: &Play) -> Result<Message, String> {
+        if !self.is_local_turn() {
+            return Err("It's not your turn.".to_owned());
+        }
+
+        self.game.apply(play)?;
+        Ok(Message::Play(play.clone()))
+    }
+
+    /// Makes a play received from the other player.
+    pub fn play_remote(&mut self, play: &Play) -> Result<(), String> {
+        if self.is_over() || self.game.turn == self.local {
+            return Err(format!("It's not your turn to play {}", play));
+        }
+
+        self.game.apply(play)
+    }
+
+    /// Records a resignation, from either end.
+    pub fn resign(&mut self, player: Disc) {
+        self.resigned = Some(player);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_message_round_trip() {
+        let messages = [
+            Message::Game {
+                color: Disc::Light,
+                transcript: parse_transcript("d3,c5").unwrap(),
+            },
+            Message::Game {
+                color: Disc::Dark,
+                transcript: vec![],
+            },
+            Message::Play(Play::Pass),
+            Message::Chat("good luck!".to_owned()),
+            Message::Resign,
+            Message::Error("Invalid move: a1".to_owned()),
+        ];
+
+        for message in messages {
+            assert_eq!(message.to_string().parse(), Ok(message));
+        }
+
+        assert!("game blue".parse::<Message>().is_err());
+        assert!("hello".parse::<Message>().is_err());
+    }
+
+    #[test]
+    fn test_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let guest = thread::spawn(move || {
+            let mut peer = Peer::connect(address).unwrap();
+            let mut incoming = peer.incoming().unwrap();
+
+            let mut network_game =
+                NetworkGame::from_message(&incoming.next().unwrap().unwrap()).unwrap();
+            assert_eq!(network_game.local, Disc::Light);
+
+            let Message::Play(play) = incoming.next().unwrap().unwrap() else {
+                panic!("expected a play");
+            };
+            network_game.play_remote(&play).unwrap();
+
+            // not our turn again yet, and a1 isn't valid anyway
+            let a1 = "a1".parse().unwrap();
+            assert!(network_game.play_remote(&a1).is_err());
+            assert!(network_game.play_local(&a1).is_err());
+
+            let reply = network_game.play_local(&"c5".parse().unwrap()).unwrap();
+            peer.send(&reply).unwrap();
+            peer.send(&Message::Chat("nice".to_owned())).unwrap();
+            peer.send(&Message::Resign).unwrap();
+        });
+
+        let mut host = NetworkGame::new(Disc::Dark);
+        let mut peer = Peer::accept(&listener).unwrap();
+        let mut incoming = peer.incoming().unwrap();
+
+        peer.send(&host.welcome()).unwrap();
+        let opening = host.play_local(&"d3".parse().unwrap()).unwrap();
+        peer.send(&opening).unwrap();
+
+        let Message::Play(reply) = incoming.next().unwrap().unwrap() else {
+            panic!("expected a play");
+        };
+        host.play_remote(&reply).unwrap();
+        assert_eq!(host.game.transcript, parse_transcript("d3,c5").unwrap());
+
+        assert_eq!(
+            incoming.next().unwrap(),
+            Ok(Message::Chat("nice".to_owned()))
+        );
+        assert_eq!(incoming.next().unwrap(), Ok(Message::Resign));
+        assert!(incoming.next().is_none());
+
+        guest.join().unwrap();
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt::*;
+use std::str::FromStr;
 
 const MAX_XY: usize = 7;
 const MAX_INDEX: usize = 63;
@@ -11,9 +12,6 @@ const Y_POSITIONS: [char; 8] = ['1', '2', '3', '4', '5', '6', '7', '8'];
 pub struct Position(usize);
 
 impl Position {
-    // TODO: convert (char, char) to Position, and Position to (char, char) for generating
-    // and consuming transcripts.
-
     /// Creates a new [Position] from the given index. Panics if the index is out of bounds.
     pub fn new(index: usize) -> Self {
         if index > MAX_INDEX {
@@ -40,7 +38,7 @@ impl Position {
         (x, y)
     }
 
-    /// Generates all of the indexes north of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes north of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_north(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
@@ -52,7 +50,7 @@ impl Position {
         output
     }
 
-    /// Generates all of the indexes north east of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes north east of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_north_east(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
@@ -64,7 +62,7 @@ impl Position {
         output
     }
 
-    /// Generates all of the indexes east of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes east of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_east(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
@@ -76,7 +74,7 @@ impl Position {
         output
     }
 
-    /// Generates all of the indexes south east of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes south east of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_south_east(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
@@ -88,7 +86,7 @@ impl Position {
         output
     }
 
-    /// Generates all of the indexes south of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes south of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_south(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
@@ -100,41 +98,62 @@ impl Position {
         output
     }
 
-    /// Generates all of the indexes south west of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes south west of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_south_west(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
         let mut index = self.0;
-        while index <= 56 && index % 8 != 0 {
+        while index <= 56 && !index.is_multiple_of(8) {
             index += 7;
             output.push(Position::new(index));
         }
         output
     }
 
-    /// Generates all of the indexes west of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes west of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_west(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
         let mut index = self.0;
-        while index % 8 != 0 && index >= 1 {
+        while !index.is_multiple_of(8) && index >= 1 {
             index -= 1;
             output.push(Position::new(index));
         }
         output
     }
 
-    /// Generates all of the indexes north west of the [Position]. Used in `desdata` to generate static data.
+    /// Generates all of the indexes north west of the [Position]. Used by `build.rs` to generate static data.
     pub fn generate_north_west(&self) -> Vec<Position> {
         let mut output = Vec::with_capacity(8);
         output.push(*self);
         let mut index = self.0;
-        while index % 8 != 0 && index >= 9 {
+        while !index.is_multiple_of(8) && index >= 9 {
             index -= 9;
             output.push(Position::new(index));
         }
         output
     }
+
+    /// Generates the lines of play from the [Position], as indexes: each direction that has
+    /// at least one other square in it, starting with the [Position] itself, from north round
+    /// to north west. Used by `build.rs` to generate static data; see
+    /// [Board::lines_for()](crate::Board::lines_for()).
+    pub fn generate_lines(&self) -> Vec<Vec<usize>> {
+        [
+            self.generate_north(),
+            self.generate_north_east(),
+            self.generate_east(),
+            self.generate_south_east(),
+            self.generate_south(),
+            self.generate_south_west(),
+            self.generate_west(),
+            self.generate_north_west(),
+        ]
+        .into_iter()
+        .filter(|line| line.len() > 1)
+        .map(|line| line.into_iter().map(|p| p.0).collect())
+        .collect()
+    }
 }
 
 impl From<Position> for usize {
@@ -149,6 +168,28 @@ impl From<&Position> for usize {
     }
 }
 
+impl FromStr for Position {
+    type Err = String;
+
+    /// Parses a coordinate such as `d3` (or `D3`) into a [Position].
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut chars = s.trim().chars().map(|c| c.to_ascii_lowercase());
+
+        let (raw_x, raw_y) = match (chars.next(), chars.next(), chars.next()) {
+            (Some(x), Some(y), None) => (x, y),
+            _ => return Err(format!("Invalid position: {}", s)),
+        };
+
+        let x = X_POSITIONS.iter().position(|c| *c == raw_x);
+        let y = Y_POSITIONS.iter().position(|c| *c == raw_y);
+
+        match (x, y) {
+            (Some(x), Some(y)) => Ok(Position::from_xy(x, y)),
+            _ => Err(format!("Invalid position: {}", s)),
+        }
+    }
+}
+
 impl Display for Position {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         let (x, y) = self.to_xy();
@@ -157,7 +198,7 @@ impl Display for Position {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
 
     use super::*;
 
@@ -174,89 +215,93 @@ mod tests {
         }
     }
 
+    /// Every line from each corner and one center square, checked by hand, from north round
+    /// to north west. Also used to check the generated data in `data.rs`.
+    #[rustfmt::skip]
+    pub(crate) const VERIFIED_LINES: [(usize, [&[usize]; 8]); 5] = [
+        (0, [
+            &[0],
+            &[0],
+            &[0, 1, 2, 3, 4, 5, 6, 7],
+            &[0, 9, 18, 27, 36, 45, 54, 63],
+            &[0, 8, 16, 24, 32, 40, 48, 56],
+            &[0],
+            &[0],
+            &[0],
+        ]),
+        (7, [
+            &[7],
+            &[7],
+            &[7],
+            &[7],
+            &[7, 15, 23, 31, 39, 47, 55, 63],
+            &[7, 14, 21, 28, 35, 42, 49, 56],
+            &[7, 6, 5, 4, 3, 2, 1, 0],
+            &[7],
+        ]),
+        (56, [
+            &[56, 48, 40, 32, 24, 16, 8, 0],
+            &[56, 49, 42, 35, 28, 21, 14, 7],
+            &[56, 57, 58, 59, 60, 61, 62, 63],
+            &[56],
+            &[56],
+            &[56],
+            &[56],
+            &[56],
+        ]),
+        (63, [
+            &[63, 55, 47, 39, 31, 23, 15, 7],
+            &[63],
+            &[63],
+            &[63],
+            &[63],
+            &[63],
+            &[63, 62, 61, 60, 59, 58, 57, 56],
+            &[63, 54, 45, 36, 27, 18, 9, 0],
+        ]),
+        // throw a center position in there for good measure
+        (27, [
+            &[27, 19, 11, 3],
+            &[27, 20, 13, 6],
+            &[27, 28, 29, 30, 31],
+            &[27, 36, 45, 54, 63],
+            &[27, 35, 43, 51, 59],
+            &[27, 34, 41, 48],
+            &[27, 26, 25, 24],
+            &[27, 18, 9, 0],
+        ]),
+    ];
+
     #[test]
     fn test_lines() {
         // test the results of every corner in every direction. Sheesh!
-        let position = Position::new(0);
-        test_list(&position.generate_north(), vec![0]);
-        test_list(&position.generate_north_east(), vec![0]);
-        test_list(&position.generate_east(), vec![0, 1, 2, 3, 4, 5, 6, 7]);
-        test_list(
-            &position.generate_south_east(),
-            vec![0, 9, 18, 27, 36, 45, 54, 63],
-        );
-        test_list(
-            &position.generate_south(),
-            vec![0, 8, 16, 24, 32, 40, 48, 56],
-        );
-        test_list(&position.generate_south_west(), vec![0]);
-        test_list(&position.generate_west(), vec![0]);
-        test_list(&position.generate_north_west(), vec![0]);
-
-        let position = Position::new(7);
-        test_list(&position.generate_north(), vec![7]);
-        test_list(&position.generate_north_east(), vec![7]);
-        test_list(&position.generate_east(), vec![7]);
-        test_list(&position.generate_south_east(), vec![7]);
-        test_list(
-            &position.generate_south(),
-            vec![7, 15, 23, 31, 39, 47, 55, 63],
-        );
-        test_list(
-            &position.generate_south_west(),
-            vec![7, 14, 21, 28, 35, 42, 49, 56],
-        );
-        test_list(&position.generate_west(), vec![7, 6, 5, 4, 3, 2, 1, 0]);
-        test_list(&position.generate_north_west(), vec![7]);
-
-        let position = Position::new(56);
-        test_list(
-            &position.generate_north(),
-            vec![56, 48, 40, 32, 24, 16, 8, 0],
-        );
-        test_list(
-            &position.generate_north_east(),
-            vec![56, 49, 42, 35, 28, 21, 14, 7],
-        );
-        test_list(
-            &position.generate_east(),
-            vec![56, 57, 58, 59, 60, 61, 62, 63],
-        );
-        test_list(&position.generate_south_east(), vec![56]);
-        test_list(&position.generate_south(), vec![56]);
-        test_list(&position.generate_south_west(), vec![56]);
-        test_list(&position.generate_west(), vec![56]);
-        test_list(&position.generate_north_west(), vec![56]);
-
-        let position = Position::new(63);
-        test_list(
-            &position.generate_north(),
-            vec![63, 55, 47, 39, 31, 23, 15, 7],
-        );
-        test_list(&position.generate_north_east(), vec![63]);
-        test_list(&position.generate_east(), vec![63]);
-        test_list(&position.generate_south_east(), vec![63]);
-        test_list(&position.generate_south(), vec![63]);
-        test_list(&position.generate_south_west(), vec![63]);
-        test_list(
-            &position.generate_west(),
-            vec![63, 62, 61, 60, 59, 58, 57, 56],
-        );
-        test_list(
-            &position.generate_north_west(),
-            vec![63, 54, 45, 36, 27, 18, 9, 0],
-        );
+        for (index, lines) in VERIFIED_LINES {
+            let position = Position::new(index);
+            test_list(&position.generate_north(), lines[0].to_vec());
+            test_list(&position.generate_north_east(), lines[1].to_vec());
+            test_list(&position.generate_east(), lines[2].to_vec());
+            test_list(&position.generate_south_east(), lines[3].to_vec());
+            test_list(&position.generate_south(), lines[4].to_vec());
+            test_list(&position.generate_south_west(), lines[5].to_vec());
+            test_list(&position.generate_west(), lines[6].to_vec());
+            test_list(&position.generate_north_west(), lines[7].to_vec());
+        }
+    }
 
-        // throw a center position in there for good measure
-        let position = Position::new(27);
-        test_list(&position.generate_north(), vec![27, 19, 11, 3]);
-        test_list(&position.generate_north_east(), vec![27, 20, 13, 6]);
-        test_list(&position.generate_east(), vec![27, 28, 29, 30, 31]);
-        test_list(&position.generate_south_east(), vec![27, 36, 45, 54, 63]);
-        test_list(&position.generate_south(), vec![27, 35, 43, 51, 59]);
-        test_list(&position.generate_south_west(), vec![27, 34, 41, 48]);
-        test_list(&position.generate_west(), vec![27, 26, 25, 24]);
-        test_list(&position.generate_north_west(), vec![27, 18, 9, 0]);
+    #[test]
+    fn test_from_str() {
+        assert_eq!("a1".parse::<Position>().unwrap(), Position::new(0));
+        assert_eq!("D3".parse::<Position>().unwrap(), Position::from_xy(3, 2));
+        assert_eq!("h8".parse::<Position>().unwrap(), Position::new(63));
+
+        for bad in ["", "a", "a9", "i1", "a12", "11"] {
+            assert!(bad.parse::<Position>().is_err());
+        }
+
+        for index in 0..64 {
+            let position = Position::new(index);
+            assert_eq!(format!("{}", position).parse::<Position>(), Ok(position));
+        }
     }
 
     fn get_indices(list: &[Position]) -> Vec<usize> {
@@ -0,0 +1,442 @@
+//! A simple line based protocol for driving out-of-process engines.
+//!
+//! The controller writes one [Command] per line to the engine's stdin, and the engine answers
+//! on stdout with one [Response] per line:
+//!
+//! | Command                   | Response                                    |
+//! |---------------------------|---------------------------------------------|
+//! | `hello`                   | `hello <name> <version>`                    |
+//! | `new`                     | `ok`                                        |
+//! | `transcript d3,c5,p`      | `ok` or `error <message>`                   |
+//! | `position <board> <X/O>`  | `ok` or `error <message>`                   |
+//! | `play d3`                 | `ok` or `error <message>`                   |
+//! | `go <milliseconds>`       | any `info <text>` lines, then `move <play> [<score>]` |
+//! | `hint <count>`            | up to `count` `hint <play> <score>` lines, best first, then `ok` |
+//! | `quit`                    | _(none; the engine exits)_                  |
+//!
+//! [Session] implements the engine side of the protocol for any [Strategy], which is how the
+//! `desengine` binary exposes Desdemona's strategies to other programs. It stops searching
+//! once `go`'s time is up (for strategies that can stop early), and sends an `info` line for
+//! each [SearchInfo](crate::strategies::SearchInfo) the search reports along the way, eg:
+//! `info depth 4 nodes 18150 time 12 score 1.5 pv d3 c5 f6`.
+//!
+//! Positions use [Board::to_position_string()], followed by `X` when dark is to play or `O`
+//! for light. Plays use the transcript notation: coordinates like `d3`, or `p` to pass.
+
+use crate::strategies::{Search, Strategy};
+use crate::{parse_transcript, Board, Disc, Game, Play, Position};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Commands sent from a controller to an engine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Hello,
+    New,
+    Transcript(Vec<Play>),
+    Position(Board, Disc),
+    Play(Play),
+    Go(Duration),
+    Hint(usize),
+    Quit,
+}
+
+/// Responses sent from an engine back to its controller.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Response {
+    Hello { name: String, version: String },
+    Ok,
+    Error(String),
+    Info(String),
+    Move { play: Play, score: Option<f32> },
+    Hint { play: Play, score: f32 },
+}
+
+pub(crate) fn turn_symbol(turn: Disc) -> char {
+    match turn {
+        Disc::Dark => 'X',
+        Disc::Light => 'O',
+    }
+}
+
+pub(crate) fn parse_turn(input: &str) -> Result<Disc, String> {
+    match input {
+        "X" | "x" | "*" => Ok(Disc::Dark),
+        "O" | "o" => Ok(Disc::Light),
+        _ => Err(format!("Invalid turn: {}", input)),
+    }
+}
+
+/// Splits a line into its keyword and the (trimmed) remainder.
+pub(crate) fn split_line(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((keyword, rest)) => (keyword, rest.trim()),
+        None => (line, ""),
+    }
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Hello => write!(f, "hello"),
+            Command::New => write!(f, "new"),
+            Command::Transcript(plays) => {
+                let plays: Vec<String> = plays.iter().map(|p| p.to_string()).collect();
+                write!(f, "transcript {}", plays.join(","))
+            }
+            Command::Position(board, turn) => write!(
+                f,
+                "position {} {}",
+                board.to_position_string(),
+                turn_symbol(*turn)
+            ),
+            Command::Play(play) => write!(f, "play {}", play),
+            Command::Go(budget) => write!(f, "go {}", budget.as_millis()),
+            Command::Hint(count) => write!(f, "hint {}", count),
+            Command::Quit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (keyword, rest) = split_line(line);
+
+        match keyword {
+            "hello" => Ok(Command::Hello),
+            "new" => Ok(Command::New),
+            "transcript" => Ok(Command::Transcript(parse_transcript(rest)?)),
+            "position" => {
+                let (board, turn) = rest
+                    .rsplit_once(char::is_whitespace)
+                    .ok_or_else(|| format!("Missing turn in: {}", line))?;
+                Ok(Command::Position(
+                    Board::from_position_string(board)?,
+                    parse_turn(turn.trim())?,
+                ))
+            }
+            "play" => Ok(Command::Play(rest.parse()?)),
+            "go" => {
+                let millis = rest
+                    .parse()
+                    .map_err(|_| format!("Invalid time budget: {}", rest))?;
+                Ok(Command::Go(Duration::from_millis(millis)))
+            }
+            "hint" => {
+                let count = rest
+                    .parse()
+                    .map_err(|_| format!("Invalid hint count: {}", rest))?;
+                Ok(Command::Hint(count))
+            }
+            "quit" => Ok(Command::Quit),
+            _ => Err(format!("Unknown command: {}", line.trim())),
+        }
+    }
+}
+
+impl Display for Response {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Hello { name, version } => write!(f, "hello {} {}", name, version),
+            Response::Ok => write!(f, "ok"),
+            Response::Error(message) => write!(f, "error {}", message),
+            Response::Info(text) => write!(f, "info {}", text),
+            Response::Move { play, score: None } => write!(f, "move {}", play),
+            Response::Move {
+                play,
+                score: Some(score),
+            } => write!(f, "move {} {}", play, score),
+            Response::Hint { play, score } => write!(f, "hint {} {}", play, score),
+        }
+    }
+}
+
+impl FromStr for Response {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let (keyword, rest) = split_line(line);
+
+        match keyword {
+            "hello" => {
+                let (name, version) = split_line(rest);
+                Ok(Response::Hello {
+                    name: name.to_owned(),
+                    version: version.to_owned(),
+                })
+            }
+            "ok" => Ok(Response::Ok),
+            "error" => Ok(Response::Error(rest.to_owned())),
+            "info" => Ok(Response::Info(rest.to_owned())),
+            "move" => {
+                let (play, score) = split_line(rest);
+                let score = match score {
+                    "" => None,
+                    score => Some(
+                        score
+                            .parse()
+                            .map_err(|_| format!("Invalid score: {}", score))?,
+                    ),
+                };
+                Ok(Response::Move {
+                    play: play.parse()?,
+                    score,
+                })
+            }
+            "hint" => {
+                let (play, score) = split_line(rest);
+                Ok(Response::Hint {
+                    play: play.parse()?,
+                    score: score
+                        .parse()
+                        .map_err(|_| format!("Invalid score: {}", score))?,
+                })
+            }
+            _ => Err(format!("Unknown response: {}", line.trim())),
+        }
+    }
+}
+
+/// The engine side of the protocol: keeps track of the game, and answers commands by asking
+/// a [Strategy] for its plays.
+pub struct Session {
+    strategy: Box<dyn Strategy>,
+    game: Game,
+}
+
+impl Session {
+    pub fn new(strategy: Box<dyn Strategy>) -> Self {
+        Self {
+            strategy,
+            game: Game::new(),
+        }
+    }
+
+    /// The game as the controller has set it up so far.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Handles a single command, returning the responses to send back in order. [Command::Quit]
+    /// has no response; it's up to the caller to stop reading commands.
+    pub fn handle(&mut self, command: Command) -> Vec<Response> {
+        let streamed = Mutex::new(vec![]);
+        let mut responses = self.handle_streaming(command, |info| {
+            streamed.lock().unwrap().push(info);
+        });
+
+        let mut all = streamed.into_inner().unwrap();
+        all.append(&mut responses);
+        all
+    }
+
+    /// Like [Session::handle()], but passes the `info` responses from a search to `send` as
+    /// they happen, rather than returning them with the rest once it's done.
+    pub fn handle_streaming(
+        &mut self,
+        command: Command,
+        send: impl Fn(Response) + Sync,
+    ) -> Vec<Response> {
+        match command {
+            Command::Hello => vec![Response::Hello {
+                name: self.strategy.name().to_owned(),
+                version: self.strategy.version().to_owned(),
+            }],
+            Command::New => {
+                self.game = Game::new();
+                vec![Response::Ok]
+            }
+            Command::Transcript(plays) => match Game::from_transcript(&plays) {
+                Some(game) => {
+                    self.game = game;
+                    vec![Response::Ok]
+                }
+                None => vec![Response::Error("Invalid transcript".to_owned())],
+            },
+            Command::Position(board, turn) => {
+                self.game = Game::from_position(board, turn);
+                vec![Response::Ok]
+            }
+            Command::Play(play) => match self.game.apply(&play) {
+                Ok(()) => vec![Response::Ok],
+                Err(e) => vec![Response::Error(e)],
+            },
+            Command::Go(budget) => {
+                let started = Instant::now();
+                // the move carries the score from the last report, if there was one
+                let score = Mutex::new(None);
+                let search = Search::new().with_info(|info| {
+                    *score.lock().unwrap() = info.score;
+                    send(Response::Info(info.to_string()));
+                });
+                let play =
+                    search.stop_after(budget, || self.strategy.search_play(&self.game, &search));
+                let play = match play {
+                    Some(index) => Play::Move(Position::new(index)),
+                    None => Play::Pass,
+                };
+                let score = *score.lock().unwrap();
+
+                vec![
+                    Response::Info(format!(
+                        "strategy {} time {}",
+                        self.strategy.name(),
+                        started.elapsed().as_millis()
+                    )),
+                    Response::Move { play, score },
+                ]
+            }
+            Command::Hint(count) => {
+                let mut scored = self.strategy.score_plays(&self.game);
+                scored.sort_by(|a, b| b.cmp(a));
+
+                let mut responses: Vec<Response> = scored
+                    .into_iter()
+                    .take(count)
+                    .map(|s| Response::Hint {
+                        play: Play::Move(Position::new(s.index)),
+                        score: s.score,
+                    })
+                    .collect();
+                responses.push(Response::Ok);
+                responses
+            }
+            Command::Quit => vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::strategies::{AlphaBeta, Maximize};
+
+    #[test]
+    fn test_command_round_trip() {
+        let game = Game::new();
+        let commands = vec![
+            Command::Hello,
+            Command::New,
+            Command::Transcript(vec![]),
+            Command::Transcript(vec![Play::Move(Position::from_xy(3, 2)), Play::Pass]),
+            Command::Position(game.board, Disc::Light),
+            Command::Play(Play::Move(Position::new(19))),
+            Command::Play(Play::Pass),
+            Command::Go(Duration::from_millis(250)),
+            Command::Hint(3),
+            Command::Quit,
+        ];
+
+        for command in commands {
+            assert_eq!(command.to_string().parse::<Command>(), Ok(command));
+        }
+
+        assert!("go soon".parse::<Command>().is_err());
+        assert!("play z9".parse::<Command>().is_err());
+        assert!("position XO X".parse::<Command>().is_err());
+        assert!("dance".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_response_round_trip() {
+        let responses = vec![
+            Response::Hello {
+                name: "ref".to_owned(),
+                version: "0.1".to_owned(),
+            },
+            Response::Ok,
+            Response::Error("Invalid move: a1".to_owned()),
+            Response::Info("depth 3".to_owned()),
+            Response::Move {
+                play: Play::Move(Position::new(19)),
+                score: None,
+            },
+            Response::Move {
+                play: Play::Pass,
+                score: Some(-2.5),
+            },
+            Response::Hint {
+                play: Play::Move(Position::new(19)),
+                score: 0.75,
+            },
+        ];
+
+        for response in responses {
+            assert_eq!(response.to_string().parse::<Response>(), Ok(response));
+        }
+
+        assert!("move d3 lots".parse::<Response>().is_err());
+    }
+
+    #[test]
+    fn test_session() {
+        let mut session = Session::new(Box::new(Maximize {}));
+
+        assert!(matches!(
+            session.handle(Command::Hello).as_slice(),
+            [Response::Hello { .. }]
+        ));
+        assert_eq!(
+            session.handle("play a1".parse().unwrap()),
+            vec![Response::Error("Invalid move: a1".to_owned())]
+        );
+        assert_eq!(
+            session.handle("transcript d3,c5".parse().unwrap()),
+            vec![Response::Ok]
+        );
+        assert_eq!(session.game().transcript.len(), 2);
+
+        // hints are best first, and finish with ok
+        let hints = session.handle(Command::Hint(2));
+        assert_eq!(hints.len(), 3);
+        assert_eq!(hints.last(), Some(&Response::Ok));
+
+        let responses = session.handle(Command::Go(Duration::from_millis(10)));
+        assert!(matches!(&responses[0], Response::Info(info) if info.starts_with("depth 1 ")));
+        match responses.last() {
+            Some(Response::Move {
+                play: Play::Move(position),
+                ..
+            }) => assert!(session
+                .game()
+                .validate_move(Disc::Dark, position.into())
+                .is_some()),
+            other => panic!("expected a move, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let mut session = Session::new(Box::new(AlphaBeta::new(3, 0)));
+        session.handle(Command::Transcript(parse_transcript("f5,d6,c3").unwrap()));
+
+        // info lines are sent as the search goes, and the move is scored
+        let streamed = Mutex::new(vec![]);
+        let responses = session.handle_streaming(Command::Go(Duration::from_secs(60)), |info| {
+            streamed.lock().unwrap().push(info)
+        });
+        let streamed = streamed.into_inner().unwrap();
+        assert_eq!(streamed.len(), 3);
+        assert!(matches!(&streamed[2], Response::Info(info) if info.starts_with("depth 3 ")));
+        assert!(matches!(
+            responses.last(),
+            Some(Response::Move { score: Some(_), .. })
+        ));
+
+        // and with no time at all, there's still a move
+        let responses = session.handle(Command::Go(Duration::ZERO));
+        assert!(matches!(
+            responses.last(),
+            Some(Response::Move {
+                play: Play::Move(_),
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,206 @@
+//! Endgame puzzles: positions where exactly one move wins.
+//!
+//! Puzzles are written one per line, as the board (in the same format as the engine
+//! protocol's `position` command), the side to move, and the winning move. Blank lines and
+//! lines starting with `#` are ignored:
+//!
+//! ```text
+//! # dark to move and win
+//! XXXXXXXOXXXXXXXOXOOXXOXOXOOOOXOOXOOOXOOOXOOOOOXO--OXXXOO--OOOOOO X a7
+//! ```
+//!
+//! Answers are checked with the endgame solver rather than against the stored solution, so a
+//! puzzle file can't mark a losing move as correct.
+
+use crate::protocol::{parse_turn, turn_symbol};
+use crate::strategies::{AlphaBeta, Random, ScoredPlay, Strategy, TranspositionTable};
+use crate::{Board, Disc, Game, Play, Position};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+
+/// Megabytes for the solver's transposition table.
+const SOLVER_TABLE: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    pub board: Board,
+    pub turn: Disc,
+    /// The one winning move
+    pub solution: Position,
+}
+
+impl Puzzle {
+    /// The puzzle's position, ready to play.
+    pub fn game(&self) -> Game {
+        Game::from_position(self.board, self.turn)
+    }
+
+    /// Solves every move to the end of the game, best first. Scores are the final disc
+    /// difference for the player to move.
+    pub fn solve(&self) -> Vec<ScoredPlay> {
+        solve(&self.game())
+    }
+
+    /// Whether `answer` wins, according to the endgame solver.
+    pub fn is_solution(&self, answer: usize) -> bool {
+        self.solve()
+            .iter()
+            .any(|s| s.index == answer && s.score > 0.0)
+    }
+
+    /// Checks that the stored solution is the only winning move.
+    pub fn verify(&self) -> Result<(), String> {
+        match Puzzle::from_game(&self.game()) {
+            Some(solved) if solved.solution == self.solution => Ok(()),
+            Some(solved) => Err(format!(
+                "the winning move is {}, not {}",
+                solved.solution, self.solution
+            )),
+            None => Err("the position doesn't have exactly one winning move".to_owned()),
+        }
+    }
+
+    /// Makes a puzzle of the game's current position, if the player to move has a choice of
+    /// moves and exactly one of them wins. This solves the position to the end, so it's only
+    /// practical with a dozen or so empty squares.
+    pub fn from_game(game: &Game) -> Option<Puzzle> {
+        if game.is_complete {
+            return None;
+        }
+
+        let scored = solve(game);
+        let winning: Vec<&ScoredPlay> = scored.iter().filter(|s| s.score > 0.0).collect();
+
+        match winning[..] {
+            [winner] if scored.len() > 1 => Some(Puzzle {
+                board: game.board,
+                turn: game.turn,
+                solution: Position::new(winner.index),
+            }),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Puzzle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            self.board.to_position_string(),
+            turn_symbol(self.turn),
+            self.solution
+        )
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = String;
+
+    /// Parses a single line of a puzzle file, eg: `<64 squares> X b1`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let [board, turn, solution] = words[..] else {
+            return Err(format!(
+                "expected a position, side to move, and solution, found \"{}\"",
+                s.trim()
+            ));
+        };
+
+        Ok(Puzzle {
+            board: Board::from_position_string(board)?,
+            turn: parse_turn(turn)?,
+            solution: solution.parse()?,
+        })
+    }
+}
+
+/// Parses a puzzle file, skipping blank lines and `#` comments.
+pub fn parse_puzzles(text: &str) -> Result<Vec<Puzzle>, String> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim().starts_with('#'))
+        .map(|(n, line)| line.parse().map_err(|e| format!("line {}: {}", n + 1, e)))
+        .collect()
+}
+
+/// Looks through a recorded game for the first position with `max_empty` or fewer empty
+/// squares that makes a puzzle.
+pub fn find_in_transcript(transcript: &[Play], max_empty: usize) -> Option<Puzzle> {
+    let mut game = Game::new();
+
+    for play in transcript {
+        if game.empty <= max_empty {
+            if let Some(puzzle) = Puzzle::from_game(&game) {
+                return Some(puzzle);
+            }
+        }
+
+        game.apply(play).ok()?;
+    }
+
+    None
+}
+
+/// Plays random games until one of them yields a puzzle with `max_empty` or fewer empty
+/// squares.
+pub fn generate(max_empty: usize) -> Puzzle {
+    loop {
+        let mut game = Game::new();
+        Random {}.solve(&mut game);
+
+        if let Some(puzzle) = find_in_transcript(&game.transcript, max_empty) {
+            return puzzle;
+        }
+    }
+}
+
+fn solve(game: &Game) -> Vec<ScoredPlay> {
+    // generating and checking puzzles solves a lot of positions, so they all share a table
+    static TABLE: OnceLock<Arc<TranspositionTable>> = OnceLock::new();
+    let table = TABLE.get_or_init(|| Arc::new(TranspositionTable::new(SOLVER_TABLE)));
+
+    let mut scored = AlphaBeta::solver()
+        .with_table(table.clone())
+        .score_plays(game);
+    scored.sort_by(|a, b| b.cmp(a));
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const PUZZLE: &str = "XXXXXXXOXXXXXXXOXOOXXOXOXOOOOXOOXOOOXOOOXOOOOOXO--OXXXOO--OOOOOO X a7";
+
+    #[test]
+    fn test_round_trip() {
+        let puzzle: Puzzle = PUZZLE.parse().unwrap();
+
+        assert_eq!(puzzle.turn, Disc::Dark);
+        assert_eq!(puzzle.to_string(), PUZZLE);
+        assert_eq!(puzzle.verify(), Ok(()));
+        assert!("--- X".parse::<Puzzle>().is_err());
+
+        let file = format!("# a comment\n\n{}\n", PUZZLE);
+        assert_eq!(parse_puzzles(&file).unwrap(), vec![puzzle]);
+        assert!(parse_puzzles("nonsense").unwrap_err().starts_with("line 1"));
+    }
+
+    #[test]
+    fn test_generate_and_verify() {
+        let puzzle = generate(6);
+
+        assert_eq!(puzzle.verify(), Ok(()));
+        assert!(puzzle.is_solution(puzzle.solution.into()));
+
+        let wrong = puzzle
+            .game()
+            .valid_moves(puzzle.turn)
+            .find(|&index| index != usize::from(puzzle.solution))
+            .unwrap();
+        assert!(!puzzle.is_solution(wrong));
+    }
+}
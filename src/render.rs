@@ -0,0 +1,292 @@
+use crate::{Board, Disc, Game};
+use std::env;
+use std::io::IsTerminal;
+
+/// ANSI escape codes used by colored themes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub board: &'static str,
+    pub dark: &'static str,
+    pub light: &'static str,
+    pub marker: &'static str,
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// The symbols (and optionally colors) used to draw discs and empty positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub dark: &'static str,
+    pub light: &'static str,
+    pub empty: &'static str,
+    pub marker: &'static str,
+    pub palette: Option<Palette>,
+}
+
+impl Theme {
+    /// Outlined dark discs and solid light discs, which look right on dark terminal themes.
+    pub const UNICODE_DARK: Theme = Theme {
+        name: "unicode-dark",
+        dark: "○",
+        light: "●",
+        empty: "·",
+        marker: "*",
+        palette: None,
+    };
+
+    /// Solid dark discs and outlined light discs, for light terminal themes.
+    pub const UNICODE_LIGHT: Theme = Theme {
+        name: "unicode-light",
+        dark: "●",
+        light: "○",
+        empty: "·",
+        marker: "*",
+        palette: None,
+    };
+
+    /// Plain ASCII, for terminals and fonts without the unicode discs.
+    pub const ASCII: Theme = Theme {
+        name: "ascii",
+        dark: "X",
+        light: "O",
+        empty: ".",
+        marker: "*",
+        palette: None,
+    };
+
+    /// Black and white discs on a green board, using ANSI colors. Works on light or dark terminals.
+    pub const COLOR: Theme = Theme {
+        name: "color",
+        dark: "●",
+        light: "●",
+        empty: "·",
+        marker: "*",
+        palette: Some(Palette {
+            board: "\x1b[42m",
+            dark: "\x1b[30m",
+            light: "\x1b[97m",
+            marker: "\x1b[33m",
+        }),
+    };
+
+    pub const ALL: [Theme; 4] = [
+        Theme::UNICODE_DARK,
+        Theme::UNICODE_LIGHT,
+        Theme::ASCII,
+        Theme::COLOR,
+    ];
+
+    /// Looks up a theme by name.
+    pub fn from_name(name: &str) -> Option<Theme> {
+        Self::ALL
+            .iter()
+            .find(|t| t.name.eq_ignore_ascii_case(name))
+            .copied()
+    }
+
+    /// The (uncolored) symbol for a position.
+    pub fn symbol(&self, disc: Option<Disc>) -> &'static str {
+        match disc {
+            Some(Disc::Dark) => self.dark,
+            Some(Disc::Light) => self.light,
+            None => self.empty,
+        }
+    }
+
+    /// Picks a theme from the environment: plain ASCII for dumb terminals, colors when writing
+    /// to a terminal (unless `NO_COLOR` is set), and otherwise the unicode variant matching the
+    /// terminal's background, as hinted by `COLORFGBG`.
+    pub fn detect() -> Theme {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        let term = env::var("TERM").unwrap_or_default();
+
+        if term == "dumb" {
+            Theme::ASCII
+        } else if !no_color && std::io::stdout().is_terminal() {
+            Theme::COLOR
+        } else if light_background(env::var("COLORFGBG").ok().as_deref()) {
+            Theme::UNICODE_LIGHT
+        } else {
+            Theme::UNICODE_DARK
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::UNICODE_DARK
+    }
+}
+
+/// `COLORFGBG` looks like `15;0`, where the last number is the background color. Colors 7 and
+/// 9 through 15 are the light ones.
+fn light_background(colorfgbg: Option<&str>) -> bool {
+    colorfgbg
+        .and_then(|v| v.rsplit(';').next())
+        .and_then(|bg| bg.parse::<u8>().ok())
+        .is_some_and(|bg| bg == 7 || (9..=15).contains(&bg))
+}
+
+/// Draws boards and games as text, according to a [Theme] and a few layout options.
+///
+/// The `Display` implementations for [Board], [Disc], and [Game] use the default renderer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Renderer {
+    pub theme: Theme,
+    /// Label the columns (a-h) and rows (1-8)
+    pub coordinates: bool,
+    /// Mark the valid moves for the player whose turn it is when rendering a [Game]
+    pub legal_moves: bool,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            coordinates: true,
+            legal_moves: false,
+        }
+    }
+}
+
+impl Renderer {
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            theme,
+            ..Self::default()
+        }
+    }
+
+    /// A renderer using [Theme::detect()].
+    pub fn detect() -> Self {
+        Self::new(Theme::detect())
+    }
+
+    /// A single disc, colored if the theme has colors.
+    pub fn disc(&self, disc: Disc) -> String {
+        let symbol = self.theme.symbol(Some(disc));
+        match self.theme.palette {
+            None => symbol.to_owned(),
+            Some(palette) => {
+                let color = match disc {
+                    Disc::Dark => palette.dark,
+                    Disc::Light => palette.light,
+                };
+                format!("{}{}{}{}", palette.board, color, symbol, RESET)
+            }
+        }
+    }
+
+    /// The board, with the theme's marker on each empty position in `markers`.
+    pub fn board(&self, board: &Board, markers: &[usize]) -> String {
+        let mut output = String::new();
+        if self.coordinates {
+            output += "  a b c d e f g h\n";
+        }
+
+        for row in 0..=7 {
+            let cells: Vec<String> = (row * 8..row * 8 + 8)
+                .map(|index| self.cell(board.get(index), markers.contains(&index)))
+                .collect();
+
+            let line = match self.theme.palette {
+                // paint the gaps between cells too, so the board is solid
+                Some(palette) => {
+                    let gap = format!("{} ", palette.board);
+                    format!("{}{}{}{}", gap, cells.join(&gap), gap, RESET)
+                }
+                None => cells.join(" "),
+            };
+
+            if self.coordinates {
+                output += &format!("{} {}\n", row + 1, line);
+            } else {
+                output += &format!("{}\n", line);
+            }
+        }
+
+        output
+    }
+
+    /// The board and score of a game, with the valid moves marked if `legal_moves` is set.
+    pub fn game(&self, game: &Game) -> String {
+        let markers: Vec<usize> = if self.legal_moves && !game.is_complete {
+            game.valid_moves(game.turn).collect()
+        } else {
+            vec![]
+        };
+
+        format!(
+            "{}\nTurn: {} Dark: {} Light: {} Empty: {}\n",
+            self.board(&game.board, &markers),
+            self.disc(game.turn),
+            game.dark,
+            game.light,
+            game.empty,
+        )
+    }
+
+    fn cell(&self, disc: Option<Disc>, marked: bool) -> String {
+        match (disc, self.theme.palette) {
+            (Some(disc), _) => self.disc(disc),
+            (None, None) if marked => self.theme.marker.to_owned(),
+            (None, None) => self.theme.empty.to_owned(),
+            (None, Some(palette)) if marked => {
+                format!(
+                    "{}{}{}{}",
+                    palette.board, palette.marker, self.theme.marker, RESET
+                )
+            }
+            (None, Some(palette)) => {
+                format!("{}{}{}", palette.board, self.theme.empty, RESET)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_default_matches_display() {
+        let game = Game::new();
+        let renderer = Renderer::default();
+
+        assert_eq!(renderer.board(&game.board, &[]), format!("{}", game.board));
+        assert_eq!(renderer.game(&game), format!("{}", game));
+        assert_eq!(renderer.disc(Disc::Dark), format!("{}", Disc::Dark));
+    }
+
+    #[test]
+    fn test_ascii_without_coordinates() {
+        let renderer = Renderer {
+            theme: Theme::ASCII,
+            coordinates: false,
+            legal_moves: true,
+        };
+
+        let output = renderer.game(&Game::new());
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[2], ". . . * . . . .");
+        assert_eq!(lines[3], ". . * O X . . .");
+        assert_eq!(lines[4], ". . . X O * . .");
+        assert_eq!(lines[9], "Turn: X Dark: 2 Light: 2 Empty: 60");
+    }
+
+    #[test]
+    fn test_themes() {
+        assert_eq!(Theme::from_name("ASCII"), Some(Theme::ASCII));
+        assert_eq!(Theme::from_name("nope"), None);
+
+        let renderer = Renderer::new(Theme::COLOR);
+        assert!(renderer.disc(Disc::Light).contains("\x1b["));
+
+        assert!(light_background(Some("0;15")));
+        assert!(!light_background(Some("15;0")));
+        assert!(!light_background(None));
+    }
+}
@@ -0,0 +1,287 @@
+//! Serde support for the core types, behind the `serde` feature.
+//!
+//! Everything uses the same compact forms as the rest of the crate, so the JSON (or whatever
+//! else) is readable and matches `deshttp`:
+//!
+//! | Type         | Form                                                                    |
+//! |--------------|-------------------------------------------------------------------------|
+//! | [Disc]       | `"dark"` or `"light"`                                                   |
+//! | [Position]   | `"d3"`                                                                  |
+//! | [Play]       | `"d3"`, or `"p"` for a pass                                             |
+//! | [Board]      | the 64 character position string, eg: `"---...OX...---"`               |
+//! | [Game]       | `{"board", "turn", "dark", "light", "empty", "transcript", "complete"}` |
+//! | [ScoredPlay] | `{"play", "score", "strategy"}`, with the strategy's registered name    |
+//!
+//! Deserializing checks what it can: boards need exactly 64 valid squares, a game's disc
+//! counts have to match its board, a game can't be complete while there are moves to play,
+//! and a scored play's strategy has to be registered. A game's transcript, unless it's empty,
+//! has to replay from the opening position to its board and turn, so games set up with
+//! [Game::from_position()] only deserialize before any plays are made.
+
+use crate::strategies::{registry, ScoredPlay};
+use crate::{Board, Disc, Game, Play, Position};
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+fn serialize_display<S: Serializer>(
+    value: &impl Display,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn deserialize_from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr<Err = String>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(de::Error::custom)
+}
+
+impl Serialize for Disc {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Disc::Dark => "dark",
+            Disc::Light => "light",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Disc {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "dark" => Ok(Disc::Dark),
+            "light" => Ok(Disc::Light),
+            other => Err(de::Error::unknown_variant(other, &["dark", "light"])),
+        }
+    }
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+impl Serialize for Play {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_display(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Play {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_from_str(deserializer)
+    }
+}
+
+impl Serialize for Board {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_position_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Board {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Board::from_position_string(&s).map_err(de::Error::custom)
+    }
+}
+
+/// How a [Game] looks serialized, before it's checked.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GameData {
+    board: Board,
+    turn: Disc,
+    dark: usize,
+    light: usize,
+    empty: usize,
+    transcript: Vec<Play>,
+    complete: bool,
+}
+
+impl Serialize for Game {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GameData {
+            board: self.board,
+            turn: self.turn,
+            dark: self.dark,
+            light: self.light,
+            empty: self.empty,
+            transcript: self.transcript.clone(),
+            complete: self.is_complete,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Game {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GameData::deserialize(deserializer)?;
+
+        // the counts come from the board, so they have to agree with it
+        let mut game = Game::from_position(data.board, data.turn);
+        if (data.dark, data.light, data.empty) != (game.dark, game.light, game.empty) {
+            return Err(de::Error::custom(format!(
+                "the scores (dark {}, light {}, empty {}) don't match the board (dark {}, light {}, empty {})",
+                data.dark, data.light, data.empty, game.dark, game.light, game.empty
+            )));
+        }
+
+        // a game isn't always marked complete as soon as neither side can move, but it can't
+        // be complete while there's a move to make
+        if data.complete && !game.is_complete {
+            return Err(de::Error::custom(
+                "the game is marked complete, but there are moves left",
+            ));
+        }
+
+        // the transcript is what undo and analysis go by, so it has to lead to the board
+        if !data.transcript.is_empty() {
+            let replayed = Game::from_transcript(&data.transcript).ok_or_else(|| {
+                de::Error::custom("the transcript isn't a legal game from the opening position")
+            })?;
+            if replayed.board != game.board || replayed.turn != game.turn {
+                return Err(de::Error::custom(
+                    "the transcript doesn't lead to the board and turn",
+                ));
+            }
+            game.transcript = data.transcript;
+        }
+
+        game.is_complete = data.complete;
+        Ok(game)
+    }
+}
+
+/// How a [ScoredPlay] looks serialized.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScoredPlayData {
+    play: Position,
+    score: f32,
+    strategy: String,
+}
+
+impl Serialize for ScoredPlay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ScoredPlayData {
+            play: Position::new(self.index),
+            score: self.score,
+            strategy: self.strategy.name().to_owned(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScoredPlay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = ScoredPlayData::deserialize(deserializer)?;
+        let strategy = registry()
+            .get(&data.strategy)
+            .map(|registration| registration.id())
+            .ok_or_else(|| de::Error::custom(format!("unknown strategy: {}", data.strategy)))?;
+
+        Ok(ScoredPlay::new(strategy, data.score, data.play.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::parse_transcript;
+    use serde_json::json;
+
+    #[test]
+    fn test_compact_forms() {
+        assert_eq!(serde_json::to_value(Disc::Light).unwrap(), json!("light"));
+        assert_eq!(
+            serde_json::to_value(Position::from_xy(3, 2)).unwrap(),
+            json!("d3")
+        );
+        assert_eq!(serde_json::to_value(Play::Pass).unwrap(), json!("p"));
+
+        for play in ["d3", "p"] {
+            let parsed: Play = serde_json::from_value(json!(play)).unwrap();
+            assert_eq!(parsed.to_string(), play);
+        }
+
+        let board = serde_json::to_value(Board::new()).unwrap();
+        assert_eq!(board.as_str().unwrap().len(), 64);
+        assert_eq!(
+            serde_json::from_value::<Board>(board).unwrap(),
+            Board::new()
+        );
+
+        assert!(serde_json::from_value::<Disc>(json!("blue")).is_err());
+        assert!(serde_json::from_value::<Position>(json!("z9")).is_err());
+        assert!(serde_json::from_value::<Board>(json!("XO--")).is_err());
+    }
+
+    #[test]
+    fn test_game_round_trip() {
+        let game = Game::from_transcript(&parse_transcript("d3,c5,f6").unwrap()).unwrap();
+        let value = serde_json::to_value(&game).unwrap();
+        assert_eq!(value["transcript"], json!(["d3", "c5", "f6"]));
+        assert_eq!(serde_json::from_value::<Game>(value).unwrap(), game);
+    }
+
+    #[test]
+    fn test_game_validation() {
+        let game = Game::new();
+        let mut value = serde_json::to_value(&game).unwrap();
+
+        value["dark"] = json!(3);
+        assert!(serde_json::from_value::<Game>(value.clone()).is_err());
+
+        value["dark"] = json!(2);
+        value["complete"] = json!(true);
+        assert!(serde_json::from_value::<Game>(value.clone()).is_err());
+
+        value["complete"] = json!(false);
+        value["extra"] = json!(1);
+        assert!(serde_json::from_value::<Game>(value).is_err());
+
+        // the transcript has to be a legal game that arrives at the board
+        let game = Game::from_transcript(&parse_transcript("d3,c5").unwrap()).unwrap();
+        let mut value = serde_json::to_value(&game).unwrap();
+        value["transcript"] = json!(["d3", "c3"]);
+        assert!(serde_json::from_value::<Game>(value.clone()).is_err());
+        value["transcript"] = json!(["a1"]);
+        assert!(serde_json::from_value::<Game>(value.clone()).is_err());
+
+        // ... unless there isn't one, for a game set up from a position
+        value["transcript"] = json!([]);
+        let game = serde_json::from_value::<Game>(value).unwrap();
+        assert_eq!(game, Game::from_position(game.board, game.turn));
+    }
+
+    #[test]
+    fn test_scored_play() {
+        let scored = ScoredPlay::new(crate::strategies::Maximize::REGISTRATION.id(), 1.5, 19);
+        let value = serde_json::to_value(&scored).unwrap();
+        assert_eq!(
+            value,
+            json!({ "play": "d3", "score": 1.5, "strategy": "maximize" })
+        );
+
+        let parsed: ScoredPlay = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!((parsed.index, parsed.strategy), (19, scored.strategy));
+
+        let mut unknown = value;
+        unknown["strategy"] = json!("nobody");
+        assert!(serde_json::from_value::<ScoredPlay>(unknown).is_err());
+    }
+}
@@ -0,0 +1,797 @@
+use crate::bitboard::squares;
+use crate::strategies::{
+    Bound, Config, ConfigError, Entry, Parameter, Registration, ScoredPlay, Search, SearchInfo,
+    Strategy, TranspositionTable,
+};
+use crate::{Disc, Game, MoveList, Play, Position, State};
+use std::sync::Arc;
+use std::thread;
+
+/// Positional weights for each square, favoring corners and edges and penalizing the squares
+/// that give corners away.
+#[rustfmt::skip]
+const WEIGHTS: [i32; 64] = [
+    100, -20,  10,   5,   5,  10, -20, 100,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+      5,  -2,  -1,  -1,  -1,  -1,  -2,   5,
+     10,  -2,  -1,  -1,  -1,  -1,  -2,  10,
+    -20, -50,  -2,  -2,  -2,  -2, -50, -20,
+    100, -20,  10,   5,   5,  10, -20, 100,
+];
+
+/// A negamax search with alpha-beta pruning. In the midgame it looks `depth` plies ahead and
+/// scores positions by mobility and square weights; once `endgame` or fewer squares are empty
+/// it searches to the end of the game.
+///
+/// Scores are from the point of view of the player to move. Solved positions score the final
+/// disc difference; heuristic scores are on roughly the same scale, so they can be compared.
+///
+/// With a [TranspositionTable], positions reached more than once are only searched once, and
+/// the best move from earlier searches is tried first. Clones share the table, and so can
+/// any number of searches; see [AlphaBeta::with_table()].
+///
+/// With more than one thread (see [AlphaBeta::with_threads()]) it's a Lazy SMP search: helper
+/// threads search the same moves in different orders and to different depths, filling the
+/// shared table with results the main search can use. That makes it faster, but no longer
+/// deterministic, since what the main search finds in the table depends on timing.
+///
+/// It ponders (see [Strategy::ponder()]) by searching the opponent's replies into the table,
+/// ready for its next play.
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    depth: usize,
+    endgame: usize,
+    table: Option<Arc<TranspositionTable>>,
+    threads: usize,
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DEPTH, Self::DEFAULT_ENDGAME)
+            .with_table(Arc::new(TranspositionTable::new(Self::DEFAULT_TABLE)))
+    }
+}
+
+impl Strategy for AlphaBeta {
+    fn name(&self) -> &str {
+        Self::REGISTRATION.name
+    }
+
+    fn version(&self) -> &str {
+        Self::REGISTRATION.version
+    }
+
+    fn next_play(&mut self, game: &Game) -> Option<usize> {
+        self.search_play(game, &Search::new())
+    }
+
+    fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
+        let state = game.state();
+        if let Some(table) = &self.table {
+            table.new_search();
+        }
+
+        let exact = self.is_endgame(&state);
+        self.score_moves(
+            &state,
+            &ordered_moves(&state),
+            self.depth,
+            exact,
+            &Search::new(),
+        )
+        .into_iter()
+        .map(|(index, score)| ScoredPlay::new(Self::REGISTRATION.id(), score, index))
+        .collect()
+    }
+
+    /// Searches one ply deep, then two, and so on up to `depth`, reporting the best play after
+    /// each; in the endgame, it goes straight to an exact search instead. Each round tries the
+    /// previous round's best plays first, and a search that's stopped plays the last finished
+    /// round's favorite.
+    fn search_play(&mut self, game: &Game, search: &Search) -> Option<usize> {
+        let state = game.state();
+        let first = *ordered_moves(&state).first()?;
+        let scored = self.deepen(&state, search);
+        Some(scored.first().map_or(first, |&(index, _)| index))
+    }
+
+    /// Deepens the search like [Strategy::search_play()], and scores every play by the last
+    /// round that finished.
+    fn search_scores(&mut self, game: &Game, search: &Search) -> Vec<ScoredPlay> {
+        self.deepen(&game.state(), search)
+            .into_iter()
+            .map(|(index, score)| ScoredPlay::new(Self::REGISTRATION.id(), score, index))
+            .collect()
+    }
+
+    /// Searches the position after each of the opponent's replies, the likeliest first, the
+    /// way [Strategy::score_plays()] will once they've played, then again a ply deeper each
+    /// time round. The results land in the table, so it needs one. It doesn't start a new
+    /// generation in the table, so they're still there for the search that follows.
+    fn ponder(&self, game: &Game, search: &Search) {
+        if self.table.is_none() {
+            return;
+        }
+
+        let state = game.state();
+        let mut replies: Vec<State> = ordered_moves(&state)
+            .iter()
+            .map(|&index| {
+                let mut reply = state;
+                reply.play(index);
+                reply
+            })
+            .collect();
+        if replies.is_empty() && !state.is_over() {
+            let mut passed = state;
+            passed.pass();
+            replies.push(passed);
+        }
+
+        let mut worker = Worker::new(search);
+        // once the search reaches the end of every line, going deeper won't change anything
+        for depth in self.depth..=self.depth.max(state.board.count(None)) {
+            for reply in &replies {
+                let exact = self.is_endgame(reply);
+                for &index in ordered_moves(reply).iter() {
+                    let mut next = *reply;
+                    next.play(index);
+                    self.negamax(
+                        &next,
+                        depth - 1,
+                        -f32::INFINITY,
+                        f32::INFINITY,
+                        exact,
+                        &mut worker,
+                    );
+                    worker.flush();
+                    if search.is_stopped() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AlphaBeta {
+    const DEFAULT_DEPTH: usize = 4;
+    const DEFAULT_ENDGAME: usize = 8;
+    const DEFAULT_TABLE: usize = 16;
+    const DEFAULT_THREADS: usize = 1;
+
+    const DEPTH: Parameter = Parameter {
+        name: "depth",
+        default: "4",
+        description: "plies searched ahead in the midgame",
+    };
+
+    const ENDGAME: Parameter = Parameter {
+        name: "endgame",
+        default: "8",
+        description: "empty squares left when it starts searching to the end of the game",
+    };
+
+    const TABLE: Parameter = Parameter {
+        name: "table",
+        default: "16",
+        description: "megabytes for its transposition table, or 0 for none",
+    };
+
+    const THREADS: Parameter = Parameter {
+        name: "threads",
+        default: "1",
+        description: "threads searching at once, sharing the table (only 1 is deterministic)",
+    };
+
+    pub const REGISTRATION: Registration = Registration {
+        name: "alphabeta",
+        version: "0.1",
+        description: "searches ahead with alpha-beta pruning, and solves endgames exactly",
+        parameters: &[Self::DEPTH, Self::ENDGAME, Self::TABLE, Self::THREADS],
+        factory: |config| Ok(Box::new(AlphaBeta::from_config(config)?)),
+    };
+
+    /// Creates an [AlphaBeta] searching `depth` plies (at least one) in the midgame, and to the
+    /// end of the game from `endgame` empty squares, without a transposition table.
+    pub fn new(depth: usize, endgame: usize) -> Self {
+        Self {
+            depth: depth.max(1),
+            endgame,
+            table: None,
+            threads: Self::DEFAULT_THREADS,
+        }
+    }
+
+    /// Uses `table` to remember positions it has searched, which can be shared with other
+    /// searches, including ones running on other threads.
+    pub fn with_table(mut self, table: Arc<TranspositionTable>) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    /// Searches with `threads` threads (at least one). Extra threads only help through the
+    /// transposition table, so they need one; see [AlphaBeta::with_table()].
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// An [AlphaBeta] that searches every position to the end of the game, for exact scores.
+    /// Only practical once most of the board is full.
+    pub fn solver() -> Self {
+        Self::new(1, 64)
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let depth: usize = config.get(&Self::DEPTH)?;
+        if depth == 0 {
+            return Err(ConfigError::invalid(
+                &Self::DEPTH,
+                "0",
+                "must be at least 1",
+            ));
+        }
+
+        let threads: usize = config.get(&Self::THREADS)?;
+        let megabytes: usize = config.get(&Self::TABLE)?;
+        if threads == 0 {
+            return Err(ConfigError::invalid(
+                &Self::THREADS,
+                "0",
+                "must be at least 1",
+            ));
+        }
+        if threads > 1 && megabytes == 0 {
+            return Err(ConfigError::invalid(
+                &Self::THREADS,
+                &threads.to_string(),
+                "needs a transposition table to share",
+            ));
+        }
+
+        let search = Self::new(depth, config.get(&Self::ENDGAME)?).with_threads(threads);
+        Ok(match megabytes {
+            0 => search,
+            megabytes => search.with_table(Arc::new(TranspositionTable::new(megabytes))),
+        })
+    }
+
+    /// The value of the position for the player to move, searched like a move would be.
+    pub fn evaluate(&self, game: &Game) -> f32 {
+        let state = game.state();
+        self.negamax(
+            &state,
+            self.depth,
+            -f32::INFINITY,
+            f32::INFINITY,
+            self.is_endgame(&state),
+            &mut Worker::new(&Search::new()),
+        )
+    }
+
+    /// The iterative deepening behind [Strategy::search_play()]: every move, scored by the
+    /// last round that finished, best first. Empty if there are no moves, or if it was stopped
+    /// before any round did.
+    fn deepen(&self, state: &State, search: &Search) -> Vec<(usize, f32)> {
+        let mut moves = ordered_moves(state);
+        if moves.is_empty() {
+            return vec![];
+        }
+        if let Some(table) = &self.table {
+            table.new_search();
+        }
+
+        // the exact search is affordable in the endgame, so the shallower rounds would only
+        // delay it
+        let rounds: Vec<(usize, bool)> = if self.is_endgame(state) {
+            vec![(state.board.count(None), true)]
+        } else {
+            (1..=self.depth).map(|depth| (depth, false)).collect()
+        };
+
+        let mut finished = vec![];
+        for (depth, exact) in rounds {
+            let mut scored = self.score_moves(state, &moves, depth, exact, search);
+            if search.is_stopped() {
+                break;
+            }
+
+            // best first, keeping ties in the order they were searched
+            scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+            for (slot, &(index, _)) in moves.iter_mut().zip(&scored) {
+                *slot = index;
+            }
+
+            let (best, score) = scored[0];
+            search.report(&SearchInfo {
+                depth,
+                nodes: search.nodes(),
+                elapsed: search.elapsed(),
+                best: Some(best),
+                score: Some(score),
+                pv: self.principal_variation(state, best, depth),
+            });
+            finished = scored;
+        }
+
+        finished
+    }
+
+    /// Scores each of `moves` by searching to `depth`, or to the end of the game if `exact`,
+    /// using any helper threads. Each gets a full window, so the scores are exact rather than
+    /// bounds. A search that's stopped only has scores for the moves it finished.
+    fn score_moves(
+        &self,
+        state: &State,
+        moves: &MoveList,
+        depth: usize,
+        exact: bool,
+        search: &Search,
+    ) -> Vec<(usize, f32)> {
+        // helpers only help through the table, and stop when the main search is done
+        let helpers = &Search::new();
+        let scored = thread::scope(|scope| {
+            if self.table.is_some() {
+                for helper in 1..self.threads {
+                    scope.spawn(move || self.help(state, moves, depth, helper, exact, helpers));
+                }
+            }
+
+            let mut worker = Worker::new(search);
+            let mut scored = Vec::with_capacity(moves.len());
+            for &index in moves.iter() {
+                let mut next = *state;
+                next.play(index);
+                let score = -self.negamax(
+                    &next,
+                    depth - 1,
+                    -f32::INFINITY,
+                    f32::INFINITY,
+                    exact,
+                    &mut worker,
+                );
+                worker.flush();
+                if search.is_stopped() {
+                    break;
+                }
+                scored.push((index, score));
+            }
+
+            helpers.stop();
+            scored
+        });
+
+        search.count(helpers.nodes());
+        scored
+    }
+
+    /// A helper thread's share of a Lazy SMP search: the same moves as the main search, but
+    /// starting from a different one, and every other helper a ply deeper.
+    fn help(
+        &self,
+        state: &State,
+        moves: &MoveList,
+        depth: usize,
+        helper: usize,
+        exact: bool,
+        search: &Search,
+    ) {
+        if moves.is_empty() {
+            return;
+        }
+
+        let mut moves = *moves;
+        let first = helper % moves.len();
+        moves.rotate_left(first);
+        let depth = depth - 1 + helper % 2;
+
+        let mut worker = Worker::new(search);
+        for &index in moves.iter() {
+            let mut next = *state;
+            next.play(index);
+            self.negamax(
+                &next,
+                depth,
+                -f32::INFINITY,
+                f32::INFINITY,
+                exact,
+                &mut worker,
+            );
+            worker.flush();
+            if search.is_stopped() {
+                return;
+            }
+        }
+    }
+
+    fn is_endgame(&self, state: &State) -> bool {
+        state.board.count(None) <= self.endgame
+    }
+
+    /// `first`, then the best replies the table remembers, up to `depth` plays in all. Without
+    /// a table, it's just `first`.
+    fn principal_variation(&self, state: &State, first: usize, depth: usize) -> Vec<Play> {
+        let mut pv = vec![Play::Move(Position::new(first))];
+        let table = match &self.table {
+            Some(table) => table,
+            None => return pv,
+        };
+
+        let mut state = *state;
+        state.play(first);
+        while pv.len() < depth && !state.is_over() {
+            let moves = state.move_mask(state.turn);
+            if moves == 0 {
+                state.pass();
+                pv.push(Play::Pass);
+                continue;
+            }
+
+            // the entry could be for another position that landed on the same slot
+            match table.probe(state.zobrist()).and_then(|entry| entry.best) {
+                Some(index) if moves & 1 << index != 0 => {
+                    state.play(index);
+                    pv.push(Play::Move(Position::new(index)));
+                }
+                _ => break,
+            }
+        }
+
+        pv
+    }
+
+    fn negamax(
+        &self,
+        state: &State,
+        depth: usize,
+        mut alpha: f32,
+        mut beta: f32,
+        exact: bool,
+        worker: &mut Worker,
+    ) -> f32 {
+        // the result is thrown away, so anything will do
+        if worker.search.is_stopped() {
+            return 0.0;
+        }
+        worker.nodes += 1;
+
+        // solved positions are good for a search of any depth
+        let draft = if exact {
+            Entry::SOLVED
+        } else {
+            depth.min(Entry::SOLVED as usize - 1) as u8
+        };
+
+        let key = match self.table {
+            Some(_) => state.zobrist(),
+            None => 0,
+        };
+        let original_alpha = alpha;
+        let mut hint = None;
+        if let Some(entry) = self.table.as_ref().and_then(|table| table.probe(key)) {
+            if entry.depth >= draft {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+                if alpha >= beta {
+                    return entry.score;
+                }
+            }
+            hint = entry.best;
+        }
+
+        let mut moves = ordered_moves(state);
+
+        if moves.is_empty() {
+            if state.move_mask(state.turn.opposite()) == 0 {
+                return final_score(state);
+            }
+
+            // pass, without spending any depth on it
+            let mut passed = *state;
+            passed.pass();
+            return -self.negamax(&passed, depth, -beta, -alpha, exact, worker);
+        }
+
+        if depth == 0 && !exact {
+            return heuristic(state, moves.len());
+        }
+
+        // the best move last time is the most likely to be best again
+        if let Some(first) = hint.and_then(|best| moves.iter().position(|&m| m == best)) {
+            moves[..=first].rotate_right(1);
+        }
+
+        let mut best = -f32::INFINITY;
+        let mut best_move = None;
+        for &index in moves.iter() {
+            let mut next = *state;
+            next.play(index);
+
+            let score = -self.negamax(&next, depth.saturating_sub(1), -beta, -alpha, exact, worker);
+            if score > best {
+                best = score;
+                best_move = Some(index);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        // a search that was stopped partway through isn't worth remembering
+        if worker.search.is_stopped() {
+            return 0.0;
+        }
+
+        if let Some(table) = &self.table {
+            let bound = if best <= original_alpha {
+                Bound::Upper
+            } else if best >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            table.store(
+                key,
+                Entry {
+                    depth: draft,
+                    bound,
+                    score: best,
+                    best: best_move,
+                },
+            );
+        }
+
+        best
+    }
+}
+
+/// One thread's share of a [Search]. It counts the positions it visits itself, so the threads
+/// aren't all contending for the search's count, and adds them in when it's [flushed].
+///
+/// [flushed]: Worker::flush()
+struct Worker<'s, 'a> {
+    search: &'s Search<'a>,
+    nodes: u64,
+}
+
+impl<'s, 'a> Worker<'s, 'a> {
+    fn new(search: &'s Search<'a>) -> Self {
+        Self { search, nodes: 0 }
+    }
+
+    fn flush(&mut self) {
+        self.search.count(self.nodes);
+        self.nodes = 0;
+    }
+}
+
+/// Valid moves, best squares first, so the pruning kicks in sooner.
+fn ordered_moves(state: &State) -> MoveList {
+    let mut moves = state.moves();
+    moves.sort_by_key(|&index| -WEIGHTS[index]);
+    moves
+}
+
+/// The disc difference at the end of the game, for the player to move.
+fn final_score(state: &State) -> f32 {
+    let difference =
+        state.board.count(Some(Disc::Dark)) as f32 - state.board.count(Some(Disc::Light)) as f32;
+    match state.turn {
+        Disc::Dark => difference,
+        Disc::Light => -difference,
+    }
+}
+
+/// Square weights and mobility, for the player to move.
+fn heuristic(state: &State, mobility: usize) -> f32 {
+    let weight = |disc: Disc| -> i32 {
+        squares(state.board.mask(Some(disc)))
+            .map(|index| WEIGHTS[index])
+            .sum()
+    };
+    let positional = weight(state.turn) - weight(state.turn.opposite());
+
+    let opponent_mobility = state.move_mask(state.turn.opposite()).count_ones() as usize;
+
+    positional as f32 / 10.0 + mobility as f32 - opponent_mobility as f32
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{parse_transcript, Board};
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_solves_endgame() {
+        // dark to move with only a1 empty: playing it flips the rest of the top row, leaving
+        // light with just h8
+        let mut board =
+            Board::from_position_string(&format!("-{}{}", "O".repeat(6), "X".repeat(57))).unwrap();
+        board.set(63, Disc::Light);
+        let game = Game::from_position(board, Disc::Dark);
+
+        let mut search = AlphaBeta::new(2, 8);
+        let scored = search.score_plays(&game);
+        assert_eq!(scored.len(), 1);
+        assert_eq!(scored[0].index, 0);
+        assert_eq!(scored[0].score, 62.0);
+    }
+
+    #[test]
+    fn test_plays_a_game() {
+        let mut game = Game::from_transcript(&parse_transcript("f5,d6,c3,d3,c4").unwrap()).unwrap();
+        let mut search = AlphaBeta::new(2, 4);
+        search.solve(&mut game);
+        assert!(game.is_complete);
+
+        assert!(AlphaBeta::from_config(&"alphabeta(depth=0)".parse().unwrap()).is_err());
+    }
+
+    /// Ten empty squares from the end of the game in `tests/fixtures/game.txt`.
+    fn endgame() -> Game {
+        let plays = parse_transcript(include_str!("../../tests/fixtures/game.txt").trim()).unwrap();
+        Game::from_transcript(&plays[..50]).unwrap()
+    }
+
+    /// Every move's score, in index order.
+    fn scores(search: &mut AlphaBeta, game: &Game) -> Vec<(usize, f32)> {
+        let mut scored: Vec<(usize, f32)> = search
+            .score_plays(game)
+            .iter()
+            .map(|s| (s.index, s.score))
+            .collect();
+        scored.sort_by_key(|&(index, _)| index);
+        scored
+    }
+
+    #[test]
+    fn test_table_agrees() {
+        let game = endgame();
+        assert_eq!(game.empty, 10);
+
+        let table = Arc::new(TranspositionTable::new(1));
+        let expected = scores(&mut AlphaBeta::solver(), &game);
+        assert_eq!(
+            scores(&mut AlphaBeta::solver().with_table(table.clone()), &game),
+            expected
+        );
+
+        // again, with the table full of the first search's results
+        assert_eq!(
+            scores(&mut AlphaBeta::solver().with_table(table), &game),
+            expected
+        );
+
+        let config = "alphabeta(table=0)".parse().unwrap();
+        assert!(AlphaBeta::from_config(&config).unwrap().table.is_none());
+    }
+
+    #[test]
+    fn test_threads() {
+        // exact scores don't depend on what the helpers leave in the table
+        let game = endgame();
+        let table = Arc::new(TranspositionTable::new(1));
+        let mut parallel = AlphaBeta::solver().with_table(table).with_threads(4);
+        assert_eq!(
+            scores(&mut parallel, &game),
+            scores(&mut AlphaBeta::solver(), &game)
+        );
+
+        // and a whole game, in the midgame too
+        let mut game = Game::new();
+        AlphaBeta::default().with_threads(3).solve(&mut game);
+        assert!(game.is_complete);
+
+        for bad in ["alphabeta(threads=0)", "alphabeta(threads=2,table=0)"] {
+            assert!(AlphaBeta::from_config(&bad.parse().unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_search_play() {
+        let reports = Mutex::new(vec![]);
+        let search = Search::new().with_info(|info| reports.lock().unwrap().push(info.clone()));
+        let mut alphabeta = AlphaBeta::new(3, 0).with_table(Arc::new(TranspositionTable::new(1)));
+        let play = alphabeta.search_play(&Game::new(), &search);
+        drop(search);
+
+        // a report for each depth, the last of them for the play it made
+        let reports = reports.into_inner().unwrap();
+        let depths: Vec<usize> = reports.iter().map(|info| info.depth).collect();
+        assert_eq!(depths, [1, 2, 3]);
+        let last = reports.last().unwrap();
+        assert_eq!(last.best, play);
+        assert_eq!(last.pv.len(), 3);
+        assert_eq!(last.pv[0], Play::Move(Position::new(play.unwrap())));
+        assert!(reports.windows(2).all(|pair| pair[0].nodes < pair[1].nodes));
+
+        // in the endgame, it goes straight to an exact search
+        let game = endgame();
+        let reports = Mutex::new(vec![]);
+        let search = Search::new().with_info(|info| reports.lock().unwrap().push(info.clone()));
+        AlphaBeta::solver().search_play(&game, &search);
+        drop(search);
+        let reports = reports.into_inner().unwrap();
+        assert_eq!(reports.len(), 1);
+        let exact = &reports[0];
+        assert_eq!(exact.depth, 10);
+        let best = AlphaBeta::solver()
+            .score_plays(&game)
+            .into_iter()
+            .max()
+            .unwrap();
+        assert_eq!(exact.score, Some(best.score));
+
+        // stopped before it starts, it still plays something
+        let stopped = Search::new();
+        stopped.stop();
+        let play = AlphaBeta::default().search_play(&game, &stopped);
+        assert!(game.validate_move(game.turn, play.unwrap()).is_some());
+        assert!(AlphaBeta::default()
+            .search_scores(&game, &stopped)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_search_scores() {
+        // the deepest round scores every move the way score_plays does
+        let game = endgame();
+        let mut scored: Vec<(usize, f32)> = AlphaBeta::solver()
+            .search_scores(&game, &Search::new())
+            .iter()
+            .map(|s| (s.index, s.score))
+            .collect();
+        scored.sort_by_key(|&(index, _)| index);
+        assert_eq!(scored, scores(&mut AlphaBeta::solver(), &game));
+
+        // dark has to pass: a1 is the only empty square, and only light can flip anything there
+        let board = Board::from_position_string(&format!("-{}O", "X".repeat(62))).unwrap();
+        let game = Game::from_position(board, Disc::Dark);
+        let mut search = AlphaBeta::new(2, 8);
+        assert!(search.search_scores(&game, &Search::new()).is_empty());
+        assert_eq!(search.search_play(&game, &Search::new()), None);
+    }
+
+    #[test]
+    fn test_ponder() {
+        let game = endgame();
+        let table = Arc::new(TranspositionTable::new(1));
+        let search = AlphaBeta::solver().with_table(table.clone());
+
+        // stopped before it starts, it learns nothing
+        let stopped = Search::new();
+        stopped.stop();
+        search.ponder(&game, &stopped);
+        assert_eq!(table.probe(game.state().zobrist()), None);
+
+        // left alone, it finishes, and has every reply's children solved in the table
+        search.ponder(&game, &Search::new());
+        for reply in game.valid_moves(game.turn) {
+            let mut replied = game.clone();
+            replied.play(reply);
+            for index in replied.valid_moves(replied.turn) {
+                let mut next = replied.state();
+                next.play(index);
+                let entry = table.probe(next.zobrist()).expect("a pondered position");
+                assert_eq!(entry.depth, Entry::SOLVED);
+            }
+
+            // and the next play scores the same as it would have without
+            let mut pondered = search.clone();
+            assert_eq!(
+                scores(&mut pondered, &replied),
+                scores(&mut AlphaBeta::solver(), &replied)
+            );
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Describes a tunable parameter that a strategy accepts, along with its default value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: &'static str,
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+/// A parsed strategy specification, such as `monte` or `monte(rounds=2000,threads=4)`.
+///
+/// Strategy names are case insensitive; parameter values are kept as written, and are only
+/// interpreted by the strategy they're handed to.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub name: String,
+    values: Vec<(String, String)>,
+}
+
+impl Config {
+    /// Parses a `name(key=value,...)` specification. The parameter list is optional.
+    pub fn parse(spec: &str) -> Result<Self, ConfigError> {
+        let spec = spec.trim();
+
+        let (name, arguments) = match spec.find('(') {
+            None => (spec, None),
+            Some(open) => {
+                let arguments = spec[open + 1..]
+                    .strip_suffix(')')
+                    .ok_or_else(|| ConfigError::Syntax(format!("missing ')' in {}", spec)))?;
+                (&spec[..open], Some(arguments))
+            }
+        };
+
+        let name = name.trim().to_ascii_lowercase();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(ConfigError::Syntax(format!(
+                "bad strategy name in {}",
+                spec
+            )));
+        }
+
+        let mut config = Config {
+            name,
+            values: vec![],
+        };
+
+        for argument in arguments.unwrap_or("").split(',') {
+            if argument.trim().is_empty() {
+                continue;
+            }
+
+            let (key, value) = argument.split_once('=').ok_or_else(|| {
+                ConfigError::Syntax(format!("expected key=value, got {}", argument))
+            })?;
+
+            config.set(key.trim(), value.trim());
+        }
+
+        Ok(config)
+    }
+
+    /// Sets (or replaces) the value of a parameter.
+    pub fn set(&mut self, key: &str, value: &str) {
+        let key = key.to_ascii_lowercase();
+        match self.values.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self.values.push((key, value.to_owned())),
+        }
+    }
+
+    /// Returns the raw value of a parameter, if it was given.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns the value of `parameter`, falling back to its default when it wasn't given.
+    pub fn get<T: FromStr>(&self, parameter: &Parameter) -> Result<T, ConfigError>
+    where
+        T::Err: Display,
+    {
+        let value = self.value(parameter.name).unwrap_or(parameter.default);
+        value
+            .parse()
+            .map_err(|e: T::Err| ConfigError::invalid(parameter, value, &e.to_string()))
+    }
+
+    /// Rejects any parameter that isn't in `parameters`.
+    pub fn validate(&self, parameters: &[Parameter]) -> Result<(), ConfigError> {
+        for (key, _) in &self.values {
+            if !parameters.iter().any(|p| p.name == key) {
+                return Err(ConfigError::UnknownParameter {
+                    strategy: self.name.clone(),
+                    parameter: key.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.values.is_empty() {
+            let values: Vec<String> = self
+                .values
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            write!(f, "({})", values.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Config {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Config::parse(s)
+    }
+}
+
+/// Reasons a strategy specification can be rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Syntax(String),
+    UnknownStrategy(String),
+    UnknownParameter {
+        strategy: String,
+        parameter: String,
+    },
+    InvalidValue {
+        parameter: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl ConfigError {
+    /// Convenience for strategies rejecting a parameter value during validation.
+    pub fn invalid(parameter: &Parameter, value: &str, reason: &str) -> Self {
+        ConfigError::InvalidValue {
+            parameter: parameter.name.to_owned(),
+            value: value.to_owned(),
+            reason: reason.to_owned(),
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Syntax(message) => write!(f, "Invalid strategy: {}", message),
+            ConfigError::UnknownStrategy(name) => write!(f, "Unknown strategy {}", name),
+            ConfigError::UnknownParameter {
+                strategy,
+                parameter,
+            } => write!(
+                f,
+                "Unknown parameter {} for strategy {}",
+                parameter, strategy
+            ),
+            ConfigError::InvalidValue {
+                parameter,
+                value,
+                reason,
+            } => write!(f, "Invalid value {} for {}: {}", value, parameter, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ROUNDS: Parameter = Parameter {
+        name: "rounds",
+        default: "100",
+        description: "",
+    };
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse("Monte").unwrap();
+        assert_eq!(config.name, "monte");
+        assert_eq!(config.value("rounds"), None);
+
+        let config = Config::parse("monte(rounds=2000, threads=4)").unwrap();
+        assert_eq!(config.name, "monte");
+        assert_eq!(config.value("rounds"), Some("2000"));
+        assert_eq!(config.value("threads"), Some("4"));
+        assert_eq!(format!("{}", config), "monte(rounds=2000,threads=4)");
+
+        assert!(Config::parse("monte(rounds=2000").is_err());
+        assert!(Config::parse("monte(rounds)").is_err());
+        assert!(Config::parse("(rounds=1)").is_err());
+    }
+
+    #[test]
+    fn test_get_and_validate() {
+        let config = Config::parse("monte").unwrap();
+        assert_eq!(config.get::<usize>(&ROUNDS).unwrap(), 100);
+
+        let config = Config::parse("monte(rounds=12)").unwrap();
+        assert_eq!(config.get::<usize>(&ROUNDS).unwrap(), 12);
+        assert!(config.validate(&[ROUNDS]).is_ok());
+        assert!(config.validate(&[]).is_err());
+
+        let config = Config::parse("monte(rounds=lots)").unwrap();
+        assert!(config.get::<usize>(&ROUNDS).is_err());
+    }
+}
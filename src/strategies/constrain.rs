@@ -1,29 +1,41 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 #[derive(Copy, Clone)]
 pub struct Constrain {}
 
+impl Constrain {
+    pub const REGISTRATION: Registration = Registration {
+        name: "constrain",
+        version: "0.1",
+        description: "tries to limit the opponent's moves",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Constrain {})),
+    };
+}
+
 impl Strategy for Constrain {
     fn name(&self) -> &str {
-        "constrain"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
-        let enabled_moves: Vec<(usize, usize)> = game
-            .valid_moves(game.turn)
-            .map(|index| {
-                // clone the game and the move and play it
-                let mut possible_game = game.clone();
-                possible_game.play(index);
+        let state = game.state();
+        let enabled_moves: Vec<(usize, usize)> = state
+            .moves()
+            .iter()
+            .map(|&index| {
+                // copy the board and play the move on it
+                let mut possible = state;
+                possible.play(index);
 
                 // count how many moves the opponent has available
-                // note: game.turn gets flipped by play()
-                let move_count = possible_game.valid_moves(game.turn).count();
+                // note: possible.turn gets flipped by play()
+                let move_count = possible.move_mask(game.turn).count_ones() as usize;
                 (index, move_count)
             })
             .collect();
@@ -43,29 +55,34 @@ impl Strategy for Constrain {
         enabled_moves
             .into_iter()
             .map(|(index, moves)| {
-                ScoredPlay::new(Strategies::Maximize, moves as f32 / max_moves as f32, index)
+                ScoredPlay::new(
+                    Self::REGISTRATION.id(),
+                    moves as f32 / max_moves as f32,
+                    index,
+                )
             })
             .collect()
     }
 
     fn next_play(&mut self, game: &Game) -> Option<usize> {
         // look for the move that blocks the most moves for the opponent
-        let mut scored_moves: Vec<(usize, usize)> = game
-            .valid_moves(game.turn)
-            .into_iter()
-            .map(|vm| {
-                // clone the game and the move and play it
-                let mut possible_game = game.clone();
-                possible_game.play(vm);
+        let state = game.state();
+        let mut scored_moves: Vec<(usize, usize)> = state
+            .moves()
+            .iter()
+            .map(|&vm| {
+                // copy the board and play the move on it
+                let mut possible = state;
+                possible.play(vm);
 
                 // count how many moves the opponent has available
-                // note: game.turn gets flipped by play()
-                let move_count = possible_game.valid_moves(game.turn).count();
+                // note: possible.turn gets flipped by play()
+                let move_count = possible.move_mask(game.turn).count_ones() as usize;
                 (move_count, vm)
             })
             .collect();
 
-        scored_moves.sort_by(|a, b| a.0.cmp(&b.0));
+        scored_moves.sort_by_key(|a| a.0);
 
         // returns None if there isn't one
         let next_move = scored_moves.pop()?.1;
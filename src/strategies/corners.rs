@@ -1,4 +1,4 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 #[derive(Copy, Clone)]
@@ -7,13 +7,23 @@ pub struct Corners {}
 const GOOD_CORNER_INDEXES: [usize; 4] = [0, 7, 56, 63];
 const BAD_CORNER_INDEXES: [usize; 12] = [1, 8, 6, 9, 14, 15, 48, 49, 54, 55, 57, 62];
 
+impl Corners {
+    pub const REGISTRATION: Registration = Registration {
+        name: "corners",
+        version: "0.1",
+        description: "prefers true corners, and avoids playing the corners' neighbors",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Corners {})),
+    };
+}
+
 impl Strategy for Corners {
     fn name(&self) -> &str {
-        "corners"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
@@ -23,11 +33,11 @@ impl Strategy for Corners {
         game.valid_moves(game.turn)
             .map(|index| {
                 if GOOD_CORNER_INDEXES.contains(&index) {
-                    ScoredPlay::new(Strategies::Corners, 1.0, index)
+                    ScoredPlay::new(Self::REGISTRATION.id(), 1.0, index)
                 } else if BAD_CORNER_INDEXES.contains(&index) {
-                    ScoredPlay::new(Strategies::Corners, 0.0, index)
+                    ScoredPlay::new(Self::REGISTRATION.id(), 0.0, index)
                 } else {
-                    ScoredPlay::new(Strategies::Corners, 0.5, index)
+                    ScoredPlay::new(Self::REGISTRATION.id(), 0.5, index)
                 }
             })
             .collect()
@@ -0,0 +1,286 @@
+use crate::protocol::{Command, Response};
+use crate::strategies::{Config, ConfigError, Parameter, Registration, ScoredPlay, Strategy};
+use crate::{Game, Play};
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command as Process, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Plays by asking an out-of-process engine for its moves, over the line based protocol
+/// described in [protocol](crate::protocol).
+///
+/// The engine is started when the strategy is created and shut down when it's dropped. An
+/// engine that crashes, stops answering, or plays an illegal move forfeits: the error is
+/// logged to stderr, kept for [ExternalEngine::failure()], and the strategy has no plays for
+/// the rest of its life.
+pub struct ExternalEngine {
+    movetime: Duration,
+    engine_name: String,
+    engine_version: String,
+    connection: Mutex<Connection>,
+    failure: Option<String>,
+}
+
+struct Connection {
+    child: Child,
+    stdin: ChildStdin,
+    responses: Receiver<String>,
+}
+
+impl ExternalEngine {
+    /// Extra time allowed for the engine to answer, beyond its time budget.
+    const GRACE: Duration = Duration::from_secs(5);
+
+    const COMMAND: Parameter = Parameter {
+        name: "command",
+        default: "",
+        description: "engine executable, followed by any arguments",
+    };
+
+    const MOVETIME: Parameter = Parameter {
+        name: "movetime",
+        default: "1000",
+        description: "milliseconds the engine is given for each move",
+    };
+
+    pub const REGISTRATION: Registration = Registration {
+        name: "external",
+        version: "0.1",
+        description: "asks an out-of-process engine for its moves",
+        parameters: &[Self::COMMAND, Self::MOVETIME],
+        factory: |config| Ok(Box::new(ExternalEngine::from_config(config)?)),
+    };
+
+    /// Starts `command` (split on whitespace into the program and its arguments) and greets it.
+    pub fn new(command: &str, movetime: Duration) -> io::Result<Self> {
+        let mut words = command.split_whitespace();
+        let program = words
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no engine command"))?;
+
+        let mut child = Process::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // read responses on their own thread so a hung engine can be timed out
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut connection = Connection {
+            child,
+            stdin,
+            responses,
+        };
+
+        let (engine_name, engine_version) =
+            match connection.request(&Command::Hello, Self::GRACE)? {
+                Response::Hello { name, version } => (name, version),
+                other => return Err(unexpected(other)),
+            };
+
+        Ok(Self {
+            movetime,
+            engine_name,
+            engine_version,
+            connection: Mutex::new(connection),
+            failure: None,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let command: String = config.get(&Self::COMMAND)?;
+        if command.trim().is_empty() {
+            return Err(ConfigError::invalid(
+                &Self::COMMAND,
+                "",
+                "an engine is required",
+            ));
+        }
+
+        let movetime: u64 = config.get(&Self::MOVETIME)?;
+
+        Self::new(&command, Duration::from_millis(movetime))
+            .map_err(|e| ConfigError::invalid(&Self::COMMAND, &command, &e.to_string()))
+    }
+
+    /// The name and version the engine reported when it started.
+    pub fn engine(&self) -> (&str, &str) {
+        (&self.engine_name, &self.engine_version)
+    }
+
+    /// Why the engine forfeited, if it has.
+    pub fn failure(&self) -> Option<&str> {
+        self.failure.as_deref()
+    }
+
+    /// Sends the game to the engine and asks it for a play, returning the play and the
+    /// engine's score for it, if it gave one.
+    pub fn request_play(&self, game: &Game) -> io::Result<(Play, Option<f32>)> {
+        let mut connection = self.connection.lock().unwrap();
+
+        // prefer sending the history, unless the game didn't start from the opening position
+        let setup = match Game::from_transcript(&game.transcript) {
+            Some(replayed) if replayed.board == game.board && replayed.turn == game.turn => {
+                Command::Transcript(game.transcript.clone())
+            }
+            _ => Command::Position(game.board, game.turn),
+        };
+
+        match connection.request(&setup, Self::GRACE)? {
+            Response::Ok => {}
+            other => return Err(unexpected(other)),
+        }
+
+        let budget = self.movetime + Self::GRACE;
+        match connection.request(&Command::Go(self.movetime), budget)? {
+            Response::Move { play, score } => Ok((play, score)),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Asks the engine for a play and checks it, or `Err` with why the engine forfeits.
+    fn checked_play(&self, game: &Game) -> Result<Option<usize>, String> {
+        let play = match self.request_play(game) {
+            Ok((play, _)) => play,
+            Err(e) => {
+                return Err(format!(
+                    "External engine {} failed: {}",
+                    self.engine_name, e
+                ))
+            }
+        };
+
+        match play {
+            Play::Pass if game.valid_moves(game.turn).next().is_none() => Ok(None),
+            Play::Move(position) if game.validate_move(game.turn, position.into()).is_some() => {
+                Ok(Some(position.into()))
+            }
+            play => Err(format!(
+                "External engine {} played an invalid move: {}",
+                self.engine_name, play
+            )),
+        }
+    }
+
+    /// The engine's play, or `None` once it has forfeited.
+    fn play_or_forfeit(&mut self, game: &Game) -> Option<usize> {
+        if self.failure.is_some() {
+            return None;
+        }
+
+        match self.checked_play(game) {
+            Ok(play) => play,
+            Err(e) => {
+                eprintln!("{}; it forfeits", e);
+                self.failure = Some(e);
+                None
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Sends a command, and waits up to `timeout` for its response. Any `info` lines sent in
+    /// the meantime are skipped.
+    fn request(&mut self, command: &Command, timeout: Duration) -> io::Result<Response> {
+        writeln!(self.stdin, "{}", command)?;
+        self.stdin.flush()?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = match self.responses.recv_timeout(remaining) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        format!("no response to: {}", command),
+                    ))
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "engine exited",
+                    ))
+                }
+            };
+
+            match line.parse::<Response>() {
+                Ok(Response::Info(_)) => continue,
+                Ok(response) => return Ok(response),
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            }
+        }
+    }
+}
+
+fn unexpected(response: Response) -> io::Error {
+    let message = match response {
+        Response::Error(message) => message,
+        other => format!("unexpected response: {}", other),
+    };
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Drop for ExternalEngine {
+    fn drop(&mut self) {
+        if let Ok(connection) = self.connection.get_mut() {
+            let _ = writeln!(connection.stdin, "{}", Command::Quit);
+            let _ = connection.stdin.flush();
+
+            // give it a moment to exit on its own before insisting
+            let deadline = Instant::now() + Duration::from_millis(500);
+            while Instant::now() < deadline {
+                if let Ok(Some(_)) = connection.child.try_wait() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            let _ = connection.child.kill();
+            let _ = connection.child.wait();
+        }
+    }
+}
+
+impl Strategy for ExternalEngine {
+    fn name(&self) -> &str {
+        Self::REGISTRATION.name
+    }
+
+    fn version(&self) -> &str {
+        Self::REGISTRATION.version
+    }
+
+    fn next_play(&mut self, game: &Game) -> Option<usize> {
+        self.play_or_forfeit(game)
+    }
+
+    fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
+        // engines only tell us their favorite, so that's the only one that scores
+        let choice = self.play_or_forfeit(game);
+        if self.failure.is_some() {
+            return vec![];
+        }
+
+        game.valid_moves(game.turn)
+            .map(|index| {
+                let score = if Some(index) == choice { 1.0 } else { 0.0 };
+                ScoredPlay::new(Self::REGISTRATION.id(), score, index)
+            })
+            .collect()
+    }
+}
@@ -1,22 +1,34 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 #[derive(Clone, Copy)]
 pub struct Maximize {}
 
+impl Maximize {
+    pub const REGISTRATION: Registration = Registration {
+        name: "maximize",
+        version: "0.1",
+        description: "plays the move that flips the maximum number of discs",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Maximize {})),
+    };
+}
+
 impl Strategy for Maximize {
     fn name(&self) -> &str {
-        "maximize"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
-        let move_flips: Vec<(usize, usize)> = game
-            .valid_moves(game.turn)
-            .map(|index| (index, game.flips_for(index).len()))
+        let state = game.state();
+        let move_flips: Vec<(usize, usize)> = state
+            .moves()
+            .iter()
+            .map(|&index| (index, state.flips(index).count_ones() as usize))
             .collect();
 
         // guard on empty, so we can do unwraps later on
@@ -33,7 +45,11 @@ impl Strategy for Maximize {
         move_flips
             .into_iter()
             .map(|(index, flips)| {
-                ScoredPlay::new(Strategies::Maximize, flips as f32 / max_flips as f32, index)
+                ScoredPlay::new(
+                    Self::REGISTRATION.id(),
+                    flips as f32 / max_flips as f32,
+                    index,
+                )
             })
             .collect()
     }
@@ -1,22 +1,34 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 #[derive(Copy, Clone)]
 pub struct Minimize {}
 
+impl Minimize {
+    pub const REGISTRATION: Registration = Registration {
+        name: "minimize",
+        version: "0.1",
+        description: "plays the move that flips the least number of discs",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Minimize {})),
+    };
+}
+
 impl Strategy for Minimize {
     fn name(&self) -> &str {
-        "minimize"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
-        let move_flips: Vec<(usize, usize)> = game
-            .valid_moves(game.turn)
-            .map(|index| (index, game.flips_for(index).len()))
+        let state = game.state();
+        let move_flips: Vec<(usize, usize)> = state
+            .moves()
+            .iter()
+            .map(|&index| (index, state.flips(index).count_ones() as usize))
             .collect();
 
         // guard on empty, so we can do unwraps later on
@@ -34,7 +46,7 @@ impl Strategy for Minimize {
             .into_iter()
             .map(|(index, flips)| {
                 ScoredPlay::new(
-                    Strategies::Minimize,
+                    Self::REGISTRATION.id(),
                     1.0 - (flips as f32 / max_flips as f32),
                     index,
                 )
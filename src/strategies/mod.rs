@@ -1,61 +1,33 @@
+mod alphabeta;
+mod config;
 mod constrain;
 mod corners;
+mod external;
 mod maximize;
 mod minimize;
 mod monte;
 mod random;
+mod registry;
+mod search;
 mod simple;
+mod transposition;
 
-use crate::Game;
+use crate::{Game, Play, Position};
+pub use alphabeta::AlphaBeta;
+pub use config::{Config, ConfigError, Parameter};
 pub use constrain::Constrain;
 pub use corners::Corners;
+pub use external::ExternalEngine;
 pub use maximize::Maximize;
 pub use minimize::Minimize;
 pub use monte::Monte;
 pub use random::Random;
+pub use registry::{
+    from_config, from_name, register, registry, Factory, Registration, Registry, StrategyId,
+};
+pub use search::{Search, SearchInfo};
 pub use simple::Simple;
-
-use std::collections::HashMap;
-
-#[derive(Hash, Eq, PartialEq)]
-pub enum Strategies {
-    Constrain,
-    Corners,
-    Maximize,
-    Minimize,
-    Monte,
-    Random,
-    Simple,
-}
-
-impl Strategies {
-    pub fn from_name(name: &str) -> Option<Box<dyn Strategy>> {
-        match name {
-            "constrain" => Some(Box::new(Constrain {})),
-            "corners" => Some(Box::new(Corners {})),
-            "maximize" => Some(Box::new(Maximize {})),
-            "minimize" => Some(Box::new(Minimize {})),
-            "monte" => Some(Box::new(Monte {})),
-            "random" => Some(Box::new(Random {})),
-            "simple" => Some(Box::new(Simple {})),
-            _ => None,
-        }
-    }
-
-    pub fn all() -> HashMap<Strategies, Box<dyn Strategy>> {
-        let mut output: HashMap<Strategies, Box<dyn Strategy>> = HashMap::new();
-
-        output.insert(Strategies::Constrain, Box::new(Constrain {}));
-        output.insert(Strategies::Corners, Box::new(Corners {}));
-        output.insert(Strategies::Maximize, Box::new(Maximize {}));
-        output.insert(Strategies::Minimize, Box::new(Minimize {}));
-        output.insert(Strategies::Monte, Box::new(Monte {}));
-        output.insert(Strategies::Random, Box::new(Random {}));
-        output.insert(Strategies::Simple, Box::new(Simple {}));
-
-        output
-    }
-}
+pub use transposition::{Bound, Entry, Replacement, TranspositionTable};
 
 pub trait Strategy: Sync {
     fn name(&self) -> &str;
@@ -77,6 +49,42 @@ pub trait Strategy: Sync {
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay>;
 
+    /// Like [Strategy::next_play()], but the search can be stopped early, and reports its
+    /// progress as it goes; see [Search]. Once stopped, it should return promptly with the
+    /// best play it has found so far.
+    ///
+    /// The default can't be stopped: it plays [Strategy::next_play()], then reports once.
+    fn search_play(&mut self, game: &Game, search: &Search) -> Option<usize> {
+        let play = self.next_play(game);
+        search.report(&SearchInfo {
+            depth: 1,
+            nodes: search.nodes(),
+            elapsed: search.elapsed(),
+            best: play,
+            score: None,
+            pv: vec![play.map_or(Play::Pass, |index| Play::Move(Position::new(index)))],
+        });
+        play
+    }
+
+    /// Like [Strategy::score_plays()], but the search can be stopped early, and reports its
+    /// progress as it goes; see [Search]. Once stopped, it should return promptly with the
+    /// scores it has so far, which may not cover every play.
+    ///
+    /// The default can't be stopped: it's [Strategy::score_plays()].
+    fn search_scores(&mut self, game: &Game, _search: &Search) -> Vec<ScoredPlay> {
+        self.score_plays(game)
+    }
+
+    /// Thinks about `game` on the opponent's time: it's their turn, and this runs on another
+    /// thread while they decide, until `search` is stopped. Whatever it learns should make
+    /// the next [Strategy::next_play()], after their reply, quicker or better.
+    ///
+    /// It may return early if there's nothing more worth doing, and should return promptly
+    /// once stopped. The default does nothing, for strategies with nothing to carry over from
+    /// one play to the next.
+    fn ponder(&self, _game: &Game, _search: &Search) {}
+
     fn bench(&mut self, count: usize) {
         for _ in 0..count {
             let mut game = Game::new();
@@ -86,13 +94,13 @@ pub trait Strategy: Sync {
 }
 
 pub struct ScoredPlay {
-    pub strategy: Strategies,
+    pub strategy: StrategyId,
     pub score: f32,
     pub index: usize,
 }
 
 impl ScoredPlay {
-    pub fn new(strategy: Strategies, score: f32, index: usize) -> Self {
+    pub fn new(strategy: StrategyId, score: f32, index: usize) -> Self {
         Self {
             strategy,
             score,
@@ -1,53 +1,236 @@
-use crate::strategies::{Random, ScoredPlay, Strategies, Strategy};
-use crate::{Disc, Game};
+use crate::bitboard::squares;
+use crate::strategies::{
+    Config, ConfigError, Parameter, Registration, ScoredPlay, Search, SearchInfo, Strategy,
+};
+use crate::{Disc, Game, Play, Position, State};
 
+use rand::prelude::*;
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::Arc;
 
-#[derive(Default, Copy, Clone)]
-pub struct Monte {}
+#[derive(Clone)]
+pub struct Monte {
+    rounds: usize,
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl Default for Monte {
+    fn default() -> Self {
+        Self {
+            rounds: Self::DEFAULT_ROUNDS,
+            pool: None,
+        }
+    }
+}
 
 impl Strategy for Monte {
     fn name(&self) -> &str {
-        "monte"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
-        game.valid_moves(game.turn)
-            .collect::<Vec<usize>>()
-            .into_par_iter()
-            .map(|vm| (self.wins_for(game, &vm), vm))
-            .map(|(wins, index)| {
-                ScoredPlay::new(Strategies::Monte, wins as f32 / Self::ROUNDS as f32, index)
+        let score = || {
+            game.valid_moves(game.turn)
+                .collect::<Vec<usize>>()
+                .into_par_iter()
+                .map(|vm| (self.wins_for(game, &vm, self.rounds), vm))
+                .map(|(wins, index)| {
+                    ScoredPlay::new(
+                        Self::REGISTRATION.id(),
+                        wins as f32 / self.rounds as f32,
+                        index,
+                    )
+                })
+                .collect()
+        };
+
+        self.install(score)
+    }
+
+    /// Plays the rounds out in batches, a tenth of them at a time, reporting the favorite
+    /// after each. A stopped search finishes the batch it's on, then plays the favorite so far.
+    fn search_play(&mut self, game: &Game, search: &Search) -> Option<usize> {
+        let first = game.valid_moves(game.turn).next()?;
+        let scored = self.search_scores(game, search);
+        Some(scored.iter().max().map_or(first, |best| best.index))
+    }
+
+    /// Plays the rounds out in batches like [Strategy::search_play()], scoring each play by
+    /// its share of wins in the rounds played. Empty if it was stopped before the first batch.
+    fn search_scores(&mut self, game: &Game, search: &Search) -> Vec<ScoredPlay> {
+        let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+        if moves.is_empty() {
+            return vec![];
+        }
+
+        let mut wins = vec![0; moves.len()];
+        let batch = self.rounds.div_ceil(Self::BATCHES);
+
+        let mut played = 0;
+        while played < self.rounds && !search.is_stopped() {
+            let rounds = batch.min(self.rounds - played);
+            let more: Vec<usize> = self.install(|| {
+                moves
+                    .par_iter()
+                    .map(|vm| self.wins_for(game, vm, rounds))
+                    .collect()
+            });
+            played += rounds;
+            search.count((rounds * moves.len()) as u64);
+
+            for (total, more) in wins.iter_mut().zip(more) {
+                *total += more;
+            }
+            let (most, &best) = wins.iter().zip(&moves).max().unwrap();
+            search.report(&SearchInfo {
+                depth: 1,
+                nodes: search.nodes(),
+                elapsed: search.elapsed(),
+                best: Some(best),
+                score: Some(*most as f32 / played as f32),
+                pv: vec![Play::Move(Position::new(best))],
+            });
+        }
+
+        if played == 0 {
+            return vec![];
+        }
+        wins.iter()
+            .zip(moves)
+            .map(|(&wins, index)| {
+                ScoredPlay::new(Self::REGISTRATION.id(), wins as f32 / played as f32, index)
             })
             .collect()
     }
 }
 
 impl Monte {
-    const ROUNDS: usize = 100;
+    const DEFAULT_ROUNDS: usize = 100;
+
+    /// How many batches [Strategy::search_play()] splits the rounds into.
+    const BATCHES: usize = 10;
+
+    const ROUNDS: Parameter = Parameter {
+        name: "rounds",
+        default: "100",
+        description: "random games played out for each candidate move",
+    };
+
+    const THREADS: Parameter = Parameter {
+        name: "threads",
+        default: "0",
+        description: "worker threads for the simulations (0 shares the global pool)",
+    };
+
+    pub const REGISTRATION: Registration = Registration {
+        name: "monte",
+        version: "0.1",
+        description: "runs a (very limited) Monte Carlo simulation of each move",
+        parameters: &[Self::ROUNDS, Self::THREADS],
+        factory: |config| Ok(Box::new(Monte::from_config(config)?)),
+    };
+
+    /// Creates a [Monte] that plays `rounds` random games per candidate move, using a
+    /// dedicated pool of `threads` workers (or rayon's global pool when `threads` is 0).
+    /// `rounds` has to be at least 1, or there'd be nothing to score the moves by.
+    pub fn new(rounds: usize, threads: usize) -> Result<Self, String> {
+        if rounds == 0 {
+            return Err("must be at least 1".to_owned());
+        }
+
+        let pool = match threads {
+            0 => None,
+            threads => Some(Arc::new(
+                ThreadPoolBuilder::new()
+                    .num_threads(threads)
+                    .build()
+                    .map_err(|e| e.to_string())?,
+            )),
+        };
+
+        Ok(Self { rounds, pool })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ConfigError> {
+        let rounds: usize = config.get(&Self::ROUNDS)?;
+        let threads: usize = config.get(&Self::THREADS)?;
+
+        Self::new(rounds, threads).map_err(|e| match rounds {
+            0 => ConfigError::invalid(&Self::ROUNDS, "0", &e),
+            _ => ConfigError::invalid(&Self::THREADS, &threads.to_string(), &e),
+        })
+    }
+
+    /// Runs `f` in this strategy's pool, if it has one.
+    fn install<T: Send>(&self, f: impl FnOnce() -> T + Send) -> T {
+        match &self.pool {
+            Some(pool) => pool.install(f),
+            None => f(),
+        }
+    }
 
-    fn wins_for(&self, game: &Game, valid_move: &usize) -> usize {
-        let mut random = Random {};
+    fn wins_for(&self, game: &Game, valid_move: &usize, rounds: usize) -> usize {
+        let mut rng = thread_rng();
         let mut wins = 0;
-        for _ in 0..Self::ROUNDS {
-            // make a copy of the game
-            let mut new_game = game.clone();
-            // update it with the given move
-            new_game.play(*valid_move);
-            // solve the remainder of plays with the random strategy
-            random.solve(&mut new_game);
+
+        // update a copy of the board with the given move
+        let mut start = game.state();
+        start.play(*valid_move);
+
+        for _ in 0..rounds {
+            // play out the remainder of the game at random, on another copy
+            let end = Self::play_out(start, &mut rng);
+            let (dark, light) = (
+                end.board.count(Some(Disc::Dark)),
+                end.board.count(Some(Disc::Light)),
+            );
 
             // tally whether this is a win for the current player (the original game.turn)
-            if ((game.light > game.dark) && game.turn == Disc::Light)
-                || ((game.dark > game.light) && game.turn == Disc::Dark)
+            if ((light > dark) && game.turn == Disc::Light)
+                || ((dark > light) && game.turn == Disc::Dark)
             {
                 wins += 1;
             }
         }
         wins
     }
+
+    /// Plays random moves until neither player can move, like the [Random](super::Random) strategy does,
+    /// but without keeping a transcript.
+    fn play_out(mut state: State, rng: &mut impl Rng) -> State {
+        loop {
+            let moves = state.move_mask(state.turn);
+            if moves == 0 {
+                if state.move_mask(state.turn.opposite()) == 0 {
+                    return state;
+                }
+                state.pass();
+                continue;
+            }
+
+            let choice = rng.gen_range(0..moves.count_ones() as usize);
+            state.play(squares(moves).nth(choice).unwrap());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Monte::new(0, 0).err().unwrap(), "must be at least 1");
+
+        let mut monte = Monte::new(10, 0).unwrap();
+        let scored = monte.score_plays(&Game::new());
+        assert_eq!(scored.len(), 4);
+        assert!(scored.iter().all(|play| (0.0..=1.0).contains(&play.score)));
+    }
 }
@@ -1,4 +1,4 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 use rand::prelude::*;
@@ -6,13 +6,23 @@ use rand::prelude::*;
 #[derive(Default, Copy, Clone)]
 pub struct Random {}
 
+impl Random {
+    pub const REGISTRATION: Registration = Registration {
+        name: "random",
+        version: "0.1",
+        description: "plays a random valid move",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Random {})),
+    };
+}
+
 impl Strategy for Random {
     fn name(&self) -> &str {
-        "random"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
@@ -20,7 +30,7 @@ impl Strategy for Random {
         game.valid_moves(game.turn)
             .map(|idx| {
                 let score = rng.gen_range(0.0..1.0);
-                ScoredPlay::new(Strategies::Random, score, idx)
+                ScoredPlay::new(Self::REGISTRATION.id(), score, idx)
             })
             .collect()
     }
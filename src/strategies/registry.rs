@@ -0,0 +1,274 @@
+use crate::strategies::{Config, ConfigError, Parameter, Strategy};
+use std::fmt::{self, Display, Formatter};
+use std::sync::{OnceLock, RwLock, RwLockReadGuard};
+
+/// Identifies a registered strategy by its (unique) name.
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq, PartialOrd, Ord)]
+pub struct StrategyId(&'static str);
+
+impl StrategyId {
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Display for StrategyId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Builds a strategy from a [Config] whose parameters have already been checked against the
+/// [Registration].
+pub type Factory = fn(&Config) -> Result<Box<dyn Strategy>, ConfigError>;
+
+/// Everything the binaries need to know to list, describe, and build a strategy.
+#[derive(Clone, Copy)]
+pub struct Registration {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub description: &'static str,
+    pub parameters: &'static [Parameter],
+    pub factory: Factory,
+}
+
+impl Registration {
+    pub const fn id(&self) -> StrategyId {
+        StrategyId::new(self.name)
+    }
+}
+
+/// A set of named strategies. Most callers want the process-wide registry, see [registry()]
+/// and [register()], which starts out populated with the built in strategies.
+#[derive(Default, Clone)]
+pub struct Registry {
+    entries: Vec<Registration>,
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the strategies that ship with this crate.
+    pub fn builtin() -> Self {
+        use crate::strategies::*;
+
+        let mut registry = Self::new();
+        for registration in [
+            AlphaBeta::REGISTRATION,
+            Constrain::REGISTRATION,
+            Corners::REGISTRATION,
+            ExternalEngine::REGISTRATION,
+            Maximize::REGISTRATION,
+            Minimize::REGISTRATION,
+            Monte::REGISTRATION,
+            Random::REGISTRATION,
+            Simple::REGISTRATION,
+        ] {
+            registry.register(registration);
+        }
+        registry
+    }
+
+    /// Adds a strategy, keeping the list sorted by name. A strategy registered under an existing
+    /// name (ignoring case, as [Registry::get()] does) replaces it, and the previous
+    /// registration is returned.
+    pub fn register(&mut self, registration: Registration) -> Option<Registration> {
+        let key = |name: &'static str| name.bytes().map(|b| b.to_ascii_lowercase());
+        match self
+            .entries
+            .binary_search_by(|r| key(r.name).cmp(key(registration.name)))
+        {
+            Ok(index) => Some(std::mem::replace(&mut self.entries[index], registration)),
+            Err(index) => {
+                self.entries.insert(index, registration);
+                None
+            }
+        }
+    }
+
+    /// Looks up a strategy by name (case insensitive).
+    pub fn get(&self, name: &str) -> Option<&Registration> {
+        self.entries
+            .iter()
+            .find(|r| r.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Iterates over every registered strategy, in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = &Registration> + '_ {
+        self.entries.iter()
+    }
+
+    /// Builds a strategy from a specification such as `minimize` or `monte(rounds=2000)`.
+    pub fn from_name(&self, spec: &str) -> Result<Box<dyn Strategy>, ConfigError> {
+        self.from_config(&Config::parse(spec)?)
+    }
+
+    /// Builds a strategy from an already parsed [Config], rejecting unknown parameters.
+    pub fn from_config(&self, config: &Config) -> Result<Box<dyn Strategy>, ConfigError> {
+        (self.registration_for(config)?.factory)(config)
+    }
+
+    /// The registration a [Config] names, once its parameters have been checked.
+    fn registration_for(&self, config: &Config) -> Result<Registration, ConfigError> {
+        let registration = self
+            .get(&config.name)
+            .ok_or_else(|| ConfigError::UnknownStrategy(config.name.clone()))?;
+
+        config.validate(registration.parameters)?;
+
+        Ok(*registration)
+    }
+
+    /// Lists every strategy with its description, parameters and defaults, for `--help` output.
+    pub fn help(&self) -> String {
+        let mut output = String::new();
+        for registration in self.iter() {
+            output += &format!("  {:<22} {}\n", registration.name, registration.description);
+            for parameter in registration.parameters {
+                let assignment = format!("{}={}", parameter.name, parameter.default);
+                output += &format!("      {:<18} {}\n", assignment, parameter.description);
+            }
+        }
+        output
+    }
+}
+
+fn global() -> &'static RwLock<Registry> {
+    static REGISTRY: OnceLock<RwLock<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(Registry::builtin()))
+}
+
+/// The process-wide strategy registry used by the binaries.
+pub fn registry() -> RwLockReadGuard<'static, Registry> {
+    global().read().unwrap()
+}
+
+/// Adds a strategy to the process-wide registry, so it's available to [from_name()] and listed
+/// alongside the built in strategies.
+pub fn register(registration: Registration) -> Option<Registration> {
+    global().write().unwrap().register(registration)
+}
+
+/// Builds a strategy from the process-wide registry, eg: `monte(rounds=2000,threads=4)`.
+pub fn from_name(spec: &str) -> Result<Box<dyn Strategy>, ConfigError> {
+    from_config(&Config::parse(spec)?)
+}
+
+/// Builds a strategy from an already parsed [Config], using the process-wide registry.
+pub fn from_config(config: &Config) -> Result<Box<dyn Strategy>, ConfigError> {
+    // the lock is released before the factory runs, so a factory can register strategies too
+    let registration = registry().registration_for(config)?;
+    (registration.factory)(config)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::strategies::{ScoredPlay, Simple};
+    use crate::Game;
+
+    struct Custom {}
+
+    impl Strategy for Custom {
+        fn name(&self) -> &str {
+            "custom"
+        }
+
+        fn version(&self) -> &str {
+            "1.0"
+        }
+
+        fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
+            Simple {}.score_plays(game)
+        }
+    }
+
+    const CUSTOM: Registration = Registration {
+        name: "custom",
+        version: "1.0",
+        description: "a downstream strategy",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Custom {})),
+    };
+
+    #[test]
+    fn test_from_name() {
+        assert_eq!(from_name("Minimize").unwrap().name(), "minimize");
+        assert_eq!(
+            from_name("monte(rounds=10,threads=1)").unwrap().name(),
+            "monte"
+        );
+
+        assert!(matches!(
+            from_name("nope"),
+            Err(ConfigError::UnknownStrategy(_))
+        ));
+        assert!(matches!(
+            from_name("simple(depth=3)"),
+            Err(ConfigError::UnknownParameter { .. })
+        ));
+        assert!(matches!(
+            from_name("monte(rounds=0)"),
+            Err(ConfigError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = Registry::builtin();
+        assert!(registry.from_name("custom").is_err());
+
+        assert!(registry.register(CUSTOM).is_none());
+        assert_eq!(registry.from_name("custom").unwrap().name(), "custom");
+        assert!(registry.help().contains("a downstream strategy"));
+
+        // names stay sorted for listings
+        let names: Vec<&str> = registry.iter().map(|r| r.name).collect();
+        let mut sorted = names.clone();
+        sorted.sort();
+        assert_eq!(names, sorted);
+
+        // replacing returns the original, whatever the case of its name
+        assert!(registry.register(CUSTOM).is_some());
+        let shouting = Registration {
+            name: "CUSTOM",
+            ..CUSTOM
+        };
+        assert_eq!(registry.register(shouting).unwrap().name, "custom");
+        assert_eq!(registry.get("custom").unwrap().name, "CUSTOM");
+        assert_eq!(
+            registry.iter().filter(|r| r.id() == shouting.id()).count(),
+            1
+        );
+        assert_eq!(names.len(), registry.iter().count());
+    }
+
+    #[test]
+    fn test_factory_can_register() {
+        const NESTED: Registration = Registration {
+            name: "nested",
+            version: "1.0",
+            description: "registers another strategy while it's built",
+            parameters: &[],
+            factory: |_| {
+                register(Registration {
+                    name: "nested-helper",
+                    ..CUSTOM
+                });
+                Ok(Box::new(Custom {}))
+            },
+        };
+
+        register(NESTED);
+        assert_eq!(from_name("nested").unwrap().name(), "custom");
+        assert!(registry().get("nested-helper").is_some());
+    }
+}
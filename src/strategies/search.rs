@@ -0,0 +1,211 @@
+use crate::Play;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A search's progress so far, as reported to [Search::with_info()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchInfo {
+    /// How many plies deep the search has finished
+    pub depth: usize,
+    /// Positions searched so far (for Monte, random games played out)
+    pub nodes: u64,
+    /// How long the search has been running
+    pub elapsed: Duration,
+    /// The best play found so far, or `None` to pass
+    pub best: Option<usize>,
+    /// The best play's score, for strategies that score their plays
+    pub score: Option<f32>,
+    /// The principal variation: the best play, then the replies the search expects
+    pub pv: Vec<Play>,
+}
+
+impl Display for SearchInfo {
+    /// Formats the info as `depth 4 nodes 18150 time 12 score 1.5 pv d3 c5 f6`, with the time
+    /// in milliseconds; the score is left out if there isn't one.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "depth {} nodes {} time {}",
+            self.depth,
+            self.nodes,
+            self.elapsed.as_millis()
+        )?;
+        if let Some(score) = self.score {
+            write!(f, " score {}", score)?;
+        }
+
+        let pv: Vec<String> = self.pv.iter().map(Play::to_string).collect();
+        write!(f, " pv {}", pv.join(" "))
+    }
+}
+
+/// A handle on a search, shared between the strategy doing it and whoever's waiting for it.
+///
+/// Any thread can [stop](Search::stop()) the search, and the strategy should notice promptly
+/// and return the best play it has found so far. As it goes, the strategy reports its
+/// progress as [SearchInfo]s, which are passed to the callback given to
+/// [Search::with_info()]. To receive them on another thread, send them down a channel:
+///
+/// ```rust
+/// use desdemona::strategies::Search;
+/// use std::sync::mpsc;
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let search = Search::new().with_info(move |info| {
+///     let _ = sender.send(info.clone());
+/// });
+/// ```
+///
+/// See [Strategy::search_play()](crate::strategies::Strategy::search_play()).
+pub struct Search<'a> {
+    stop: AtomicBool,
+    nodes: AtomicU64,
+    started: Instant,
+    info: Option<Callback<'a>>,
+}
+
+/// What [Search::with_info()] calls with each report.
+type Callback<'a> = Box<dyn Fn(&SearchInfo) + Sync + 'a>;
+
+impl<'a> Search<'a> {
+    /// A search that runs until it's done (or stopped), starting the clock now.
+    pub fn new() -> Self {
+        Self {
+            stop: AtomicBool::new(false),
+            nodes: AtomicU64::new(0),
+            started: Instant::now(),
+            info: None,
+        }
+    }
+
+    /// Calls `info` with each report of the search's progress. It's called on whichever thread
+    /// is searching, so it shouldn't take long.
+    pub fn with_info(mut self, info: impl Fn(&SearchInfo) + Sync + 'a) -> Self {
+        self.info = Some(Box::new(info));
+        self
+    }
+
+    /// Asks the search to stop, from any thread.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Runs `f`, which should do the search, and stops the search if it's still going after
+    /// `limit`.
+    pub fn stop_after<T>(&self, limit: Duration, f: impl FnOnce() -> T) -> T {
+        let (done, finished) = mpsc::channel::<()>();
+
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                // finishing drops the sender, which wakes this up early
+                if let Err(RecvTimeoutError::Timeout) = finished.recv_timeout(limit) {
+                    self.stop();
+                }
+            });
+
+            let result = f();
+            drop(done);
+            result
+        })
+    }
+
+    /// Adds to the count of positions searched.
+    pub fn count(&self, nodes: u64) {
+        self.nodes.fetch_add(nodes, Ordering::Relaxed);
+    }
+
+    /// How many positions have been searched so far.
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// How long since the search was created.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Passes the search's progress on to the callback, if there is one.
+    pub fn report(&self, info: &SearchInfo) {
+        if let Some(callback) = &self.info {
+            callback(info);
+        }
+    }
+}
+
+impl Default for Search<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for Search<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Search")
+            .field("stopped", &self.is_stopped())
+            .field("nodes", &self.nodes())
+            .field("elapsed", &self.elapsed())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::Position;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_stop_after() {
+        let search = Search::new();
+        let waited = search.stop_after(Duration::from_millis(10), || {
+            while !search.is_stopped() {
+                thread::sleep(Duration::from_millis(1));
+            }
+            search.elapsed()
+        });
+        assert!(waited >= Duration::from_millis(10));
+
+        // finishing in time leaves it running, and doesn't wait for the limit
+        let search = Search::new();
+        search.stop_after(Duration::from_secs(60), || search.count(3));
+        assert!(!search.is_stopped());
+        assert!(search.elapsed() < Duration::from_secs(60));
+        assert_eq!(search.nodes(), 3);
+    }
+
+    #[test]
+    fn test_info() {
+        let reports = Mutex::new(vec![]);
+        let search = Search::new().with_info(|info| reports.lock().unwrap().push(info.clone()));
+
+        let info = SearchInfo {
+            depth: 4,
+            nodes: 18150,
+            elapsed: Duration::from_millis(12),
+            best: Some(19),
+            score: Some(1.5),
+            pv: vec![Play::Move(Position::new(19)), Play::Pass],
+        };
+        search.report(&info);
+        drop(search);
+
+        assert_eq!(reports.into_inner().unwrap(), vec![info.clone()]);
+        assert_eq!(
+            info.to_string(),
+            "depth 4 nodes 18150 time 12 score 1.5 pv d3 p"
+        );
+        let unscored = SearchInfo {
+            score: None,
+            ..info
+        };
+        assert_eq!(unscored.to_string(), "depth 4 nodes 18150 time 12 pv d3 p");
+    }
+}
@@ -1,16 +1,26 @@
-use crate::strategies::{ScoredPlay, Strategies, Strategy};
+use crate::strategies::{Registration, ScoredPlay, Strategy};
 use crate::Game;
 
 #[derive(Copy, Clone)]
 pub struct Simple {}
 
+impl Simple {
+    pub const REGISTRATION: Registration = Registration {
+        name: "simple",
+        version: "0.1",
+        description: "plays the first move it discovers",
+        parameters: &[],
+        factory: |_| Ok(Box::new(Simple {})),
+    };
+}
+
 impl Strategy for Simple {
     fn name(&self) -> &str {
-        "simple"
+        Self::REGISTRATION.name
     }
 
     fn version(&self) -> &str {
-        "0.1"
+        Self::REGISTRATION.version
     }
 
     fn score_plays(&mut self, game: &Game) -> Vec<ScoredPlay> {
@@ -21,9 +31,9 @@ impl Strategy for Simple {
             .map(|idx| {
                 // won't reach this unless theres a first(), so ...
                 if idx == valid_moves.first().unwrap() {
-                    ScoredPlay::new(Strategies::Simple, 1.0, *idx)
+                    ScoredPlay::new(Self::REGISTRATION.id(), 1.0, *idx)
                 } else {
-                    ScoredPlay::new(Strategies::Simple, 0.5, *idx)
+                    ScoredPlay::new(Self::REGISTRATION.id(), 0.5, *idx)
                 }
             })
             .collect()
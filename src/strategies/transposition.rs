@@ -0,0 +1,294 @@
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+/// How a stored score relates to the position's true value, given the alpha-beta window it
+/// was searched with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Bound {
+    /// The score is the position's value
+    Exact,
+    /// The search failed high: the value is at least the score
+    Lower,
+    /// The search failed low: the value is at most the score
+    Upper,
+}
+
+/// What a search learned about a position.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Entry {
+    /// How many plies deep the position was searched; [Entry::SOLVED] if to the end of the game
+    pub depth: u8,
+    pub bound: Bound,
+    /// The score, for the player to move
+    pub score: f32,
+    /// The best move found, if there was one
+    pub best: Option<usize>,
+}
+
+impl Entry {
+    /// The depth of a position searched to the end of the game, which is deeper than any
+    /// depth-limited search.
+    pub const SOLVED: u8 = u8::MAX;
+
+    // how an entry is packed into a u64: the score's bits, then the depth, bound, best move
+    // (64 for none), and the generation it was stored in
+    const DEPTH_SHIFT: u32 = 32;
+    const BOUND_SHIFT: u32 = 40;
+    const BEST_SHIFT: u32 = 42;
+    const GENERATION_SHIFT: u32 = 49;
+
+    fn pack(&self, generation: u8) -> u64 {
+        let bound: u64 = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+
+        self.score.to_bits() as u64
+            | (self.depth as u64) << Self::DEPTH_SHIFT
+            | bound << Self::BOUND_SHIFT
+            | (self.best.unwrap_or(64) as u64) << Self::BEST_SHIFT
+            | (generation as u64) << Self::GENERATION_SHIFT
+    }
+
+    /// The entry and its generation, or `None` for an empty slot.
+    fn unpack(data: u64) -> Option<(Self, u8)> {
+        let bound = match (data >> Self::BOUND_SHIFT) & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+
+        let best = ((data >> Self::BEST_SHIFT) & 0x7f) as usize;
+        let entry = Self {
+            depth: (data >> Self::DEPTH_SHIFT) as u8,
+            bound,
+            score: f32::from_bits(data as u32),
+            best: (best < 64).then_some(best),
+        };
+
+        Some((entry, (data >> Self::GENERATION_SHIFT) as u8))
+    }
+}
+
+/// Which entry a [TranspositionTable] keeps when two positions want the same slot.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Replacement {
+    /// The newest entry always wins
+    Always,
+    /// The newest entry wins, unless the old one was searched deeper during the current
+    /// search (see [TranspositionTable::new_search()])
+    #[default]
+    Deeper,
+}
+
+/// A fixed-size cache of search results, keyed by
+/// [State::zobrist()](crate::State::zobrist()), that any number of threads can share without
+/// locking.
+///
+/// Each slot is two atomic words: the entry, and the entry xor'd with its key. A reader only
+/// trusts a slot if the two agree with the key it's looking for, so an entry torn by two
+/// threads writing at once just looks like a miss. Positions whose keys land on the same slot
+/// compete for it according to the table's [Replacement] policy.
+pub struct TranspositionTable {
+    slots: Vec<[AtomicU64; 2]>,
+    replacement: Replacement,
+    generation: AtomicU8,
+}
+
+impl TranspositionTable {
+    /// The bytes used by each slot.
+    const SLOT_SIZE: usize = std::mem::size_of::<[AtomicU64; 2]>();
+
+    /// A table using at most `megabytes` of memory (and at least one slot), with the
+    /// [Replacement::Deeper] policy.
+    pub fn new(megabytes: usize) -> Self {
+        Self::with_replacement(megabytes, Replacement::default())
+    }
+
+    /// A table using at most `megabytes` of memory (and at least one slot), with the given
+    /// replacement policy.
+    pub fn with_replacement(megabytes: usize, replacement: Replacement) -> Self {
+        // a power of two, so a slot can be picked by masking the key
+        let wanted = (megabytes << 20) / Self::SLOT_SIZE;
+        let len = match wanted {
+            0 => 1,
+            wanted if wanted.is_power_of_two() => wanted,
+            wanted => wanted.next_power_of_two() / 2,
+        };
+
+        Self {
+            slots: (0..len).map(|_| Default::default()).collect(),
+            replacement,
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    /// The number of entries the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Marks the start of a new search, so that [Replacement::Deeper] lets the entries from
+    /// earlier searches be replaced.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Looks up the entry for the position with the given key.
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let [check, data] = self.slot(key);
+        let data = data.load(Ordering::Relaxed);
+
+        if check.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+
+        Entry::unpack(data).map(|(entry, _)| entry)
+    }
+
+    /// Stores the entry for the position with the given key, unless the replacement policy
+    /// keeps what's there.
+    pub fn store(&self, key: u64, entry: Entry) {
+        let [check, data] = self.slot(key);
+        let generation = self.generation.load(Ordering::Relaxed);
+
+        if self.replacement == Replacement::Deeper {
+            let old = data.load(Ordering::Relaxed);
+            if let Some((old_entry, old_generation)) = Entry::unpack(old) {
+                // the same position is always updated; a different one only if it's stale
+                // or shallower
+                let same = check.load(Ordering::Relaxed) ^ old == key;
+                if !same && old_generation == generation && old_entry.depth > entry.depth {
+                    return;
+                }
+            }
+        }
+
+        let packed = entry.pack(generation);
+        data.store(packed, Ordering::Relaxed);
+        check.store(key ^ packed, Ordering::Relaxed);
+    }
+
+    /// Empties the table.
+    pub fn clear(&self) {
+        for [check, data] in &self.slots {
+            check.store(0, Ordering::Relaxed);
+            data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn slot(&self, key: u64) -> &[AtomicU64; 2] {
+        &self.slots[key as usize & (self.slots.len() - 1)]
+    }
+}
+
+impl Debug for TranspositionTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TranspositionTable")
+            .field("capacity", &self.capacity())
+            .field("replacement", &self.replacement)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::State;
+    use rayon::prelude::*;
+
+    /// The keys of different positions: the opening, then the positions after each play.
+    fn keys(count: usize) -> Vec<u64> {
+        let mut states = vec![State::new()];
+        let mut n = 0;
+        while states.len() < count {
+            let state = states[n];
+            for &index in state.moves().iter() {
+                let mut child = state;
+                child.play(index);
+                states.push(child);
+            }
+            n += 1;
+        }
+
+        states.iter().take(count).map(State::zobrist).collect()
+    }
+
+    fn entry(depth: u8, score: f32) -> Entry {
+        Entry {
+            depth,
+            bound: Bound::Exact,
+            score,
+            best: Some(19),
+        }
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let table = TranspositionTable::new(1);
+        assert_eq!(table.capacity(), 1 << 16);
+
+        let [a, b] = [keys(2)[0], keys(2)[1]];
+        assert_eq!(table.probe(a), None);
+
+        let stored = Entry {
+            depth: Entry::SOLVED,
+            bound: Bound::Upper,
+            score: -12.5,
+            best: None,
+        };
+        table.store(a, stored);
+        assert_eq!(table.probe(a), Some(stored));
+        assert_eq!(table.probe(b), None);
+
+        table.clear();
+        assert_eq!(table.probe(a), None);
+    }
+
+    #[test]
+    fn test_replacement() {
+        // one slot, so every position competes for it
+        let [a, b] = [keys(2)[0], keys(2)[1]];
+
+        let deeper = TranspositionTable::new(0);
+        deeper.store(a, entry(6, 1.0));
+        deeper.store(b, entry(2, 2.0));
+        assert_eq!(deeper.probe(b), None);
+
+        // the same position can always be updated
+        deeper.store(a, entry(1, 3.0));
+        assert_eq!(deeper.probe(a), Some(entry(1, 3.0)));
+
+        // and anything from an earlier search gives way
+        deeper.new_search();
+        deeper.store(b, entry(0, 4.0));
+        assert_eq!(deeper.probe(b), Some(entry(0, 4.0)));
+
+        let always = TranspositionTable::with_replacement(0, Replacement::Always);
+        always.store(a, entry(6, 1.0));
+        always.store(b, entry(2, 2.0));
+        assert_eq!(always.probe(a), None);
+        assert_eq!(always.probe(b), Some(entry(2, 2.0)));
+    }
+
+    #[test]
+    fn test_shared_between_threads() {
+        // a tiny table, so the threads fight over slots
+        let table = TranspositionTable::with_replacement(0, Replacement::Always);
+        let keys = keys(40);
+
+        (0..10_000).into_par_iter().for_each(|n| {
+            let key = keys[n % keys.len()];
+            table.store(key, entry(n as u8, key as f32));
+
+            // whatever comes back has to be intact, and for the right position
+            if let Some(found) = table.probe(key) {
+                assert_eq!(found.score, key as f32);
+                assert_eq!(found.best, Some(19));
+            }
+        });
+    }
+}
@@ -0,0 +1,86 @@
+use desdemona::ggf::GgfGame;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SERVER: &str = env!("CARGO_BIN_EXE_desserver");
+const ENGINE: &str = env!("CARGO_BIN_EXE_desengine");
+
+/// Kills the child when the test is over, pass or fail.
+struct Running(Child);
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+#[test]
+fn test_engines_play_on_the_server() {
+    let record = std::env::temp_dir().join(format!("desserver-test-{}.ggf", std::process::id()));
+    let _ = fs::remove_file(&record);
+
+    let mut server = Running(
+        Command::new(SERVER)
+            .args(["--port", "0", "--clock", "60", "--record"])
+            .arg(&record)
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap(),
+    );
+
+    // find the port the server ended up on, then keep its output from backing up
+    let mut lines = BufReader::new(server.0.stdout.take().unwrap()).lines();
+    let port = lines
+        .by_ref()
+        .map_while(Result::ok)
+        .find_map(|line| {
+            Some(
+                line.split_once("listening on ")?
+                    .1
+                    .rsplit(':')
+                    .next()?
+                    .to_owned(),
+            )
+        })
+        .unwrap();
+    thread::spawn(move || lines.for_each(drop));
+
+    let address = format!("127.0.0.1:{}", port);
+    let _engines: Vec<Running> = ["random", "maximize"]
+        .iter()
+        .map(|strategy| {
+            Running(
+                Command::new(ENGINE)
+                    .args(["--connect", &address, "-s", strategy])
+                    .spawn()
+                    .unwrap(),
+            )
+        })
+        .collect();
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let games = loop {
+        // the last game may be only partly written
+        let text = fs::read_to_string(&record).unwrap_or_default();
+        let written = &text[..text.rfind('\n').map_or(0, |end| end + 1)];
+        if written.lines().count() >= 2 {
+            break GgfGame::parse_all(written).unwrap();
+        }
+        assert!(Instant::now() < deadline, "no games were recorded");
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    for ggf in games {
+        let mut players = [ggf.tag("PB").unwrap(), ggf.tag("PW").unwrap()];
+        players.sort();
+        assert_eq!(players, ["maximize", "random"]);
+        assert!(ggf.to_game().unwrap().is_complete);
+    }
+
+    drop(server);
+    let _ = fs::remove_file(&record);
+}
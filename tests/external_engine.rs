@@ -0,0 +1,100 @@
+use desdemona::strategies::*;
+use desdemona::{Board, Disc, Game, Play};
+use std::fs;
+use std::time::Duration;
+
+const REFERENCE_ENGINE: &str = env!("CARGO_BIN_EXE_desref");
+
+#[test]
+fn test_reference_engine_handshake() {
+    let engine = ExternalEngine::new(REFERENCE_ENGINE, Duration::from_millis(100)).unwrap();
+    let (name, _) = engine.engine();
+    assert_eq!(name, "desref");
+}
+
+#[test]
+fn test_reference_engine_plays_a_full_game() {
+    let spec = format!("external(command={},movetime=50)", REFERENCE_ENGINE);
+    let mut engine = from_name(&spec).unwrap();
+    let mut random = Random {};
+
+    let mut game = Game::new();
+    while !game.is_complete {
+        let play = match game.turn {
+            Disc::Dark => engine.next_play(&game),
+            Disc::Light => random.next_play(&game),
+        };
+
+        match play {
+            Some(index) => game.play(index),
+            None => game.pass(),
+        };
+    }
+
+    assert_eq!(Game::from_transcript(&game.transcript).unwrap(), game);
+}
+
+#[test]
+fn test_reference_engine_from_position() {
+    // a position that doesn't come from a transcript is sent as a position instead
+    let mut opening = Game::new();
+    opening.play(19);
+    let board = Board::from_position_string(&opening.board.to_position_string()).unwrap();
+    let game = Game::from_position(board, Disc::Light);
+
+    let engine = ExternalEngine::new(REFERENCE_ENGINE, Duration::from_millis(50)).unwrap();
+    let (play, score) = engine.request_play(&game).unwrap();
+
+    match play {
+        Play::Move(position) => assert!(game.validate_move(game.turn, position.into()).is_some()),
+        Play::Pass => panic!("expected a move"),
+    }
+    assert!(score.is_some());
+}
+
+#[test]
+fn test_missing_engine() {
+    assert!(from_name("external").is_err());
+    assert!(from_name("external(command=/nonexistent/engine)").is_err());
+}
+
+#[test]
+fn test_misbehaving_engine_forfeits() {
+    // an engine that always plays a1, which is never a move at the opening
+    let script = std::env::temp_dir().join(format!("desdemona-liar-{}.sh", std::process::id()));
+    fs::write(
+        &script,
+        "while read line; do\n\
+         case \"$line\" in\n\
+         hello) echo 'hello liar 1.0' ;;\n\
+         go*) echo 'move a1' ;;\n\
+         quit) exit ;;\n\
+         *) echo ok ;;\n\
+         esac\n\
+         done\n",
+    )
+    .unwrap();
+
+    let command = format!("sh {}", script.display());
+    let mut engine = ExternalEngine::new(&command, Duration::from_millis(50)).unwrap();
+    let game = Game::new();
+    assert_eq!(engine.next_play(&game), None);
+    assert!(engine.failure().unwrap().contains("invalid move: a1"));
+
+    // and once it has forfeited, it isn't asked again
+    assert!(engine.score_plays(&game).is_empty());
+    drop(engine);
+    fs::remove_file(script).unwrap();
+}
+
+#[test]
+fn test_desengine_as_external_engine() {
+    // desdemona's own strategies, driven through the engine protocol
+    let command = format!("{} -s constrain", env!("CARGO_BIN_EXE_desengine"));
+    let mut engine = ExternalEngine::new(&command, Duration::from_millis(50)).unwrap();
+    assert_eq!(engine.engine().0, "constrain");
+
+    let mut game = Game::new();
+    engine.solve(&mut game);
+    assert!(game.is_complete);
+}
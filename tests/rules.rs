@@ -0,0 +1,232 @@
+//! Property tests for the rules engine. Games and boards are generated at random, and checked
+//! against invariants and against `naive`, a deliberately simple reference implementation of
+//! the rules that walks the board by coordinates.
+
+use desdemona::board::Board;
+use desdemona::disc::Disc;
+use desdemona::game::{Game, Play};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+/// The rules, written as plainly as possible, with nothing shared with the crate.
+mod naive {
+    use desdemona::board::Board;
+    use desdemona::disc::Disc;
+
+    const DIRECTIONS: [(isize, isize); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+
+    /// The discs flipped if `player` plays at `index`, sorted. Empty if it isn't a move.
+    pub fn flips(board: &Board, player: Disc, index: usize) -> Vec<usize> {
+        let mut flips = vec![];
+        if board.get(index).is_some() {
+            return flips;
+        }
+
+        let (x, y) = ((index % 8) as isize, (index / 8) as isize);
+        for (dx, dy) in DIRECTIONS {
+            let mut line = vec![];
+            let (mut cx, mut cy) = (x + dx, y + dy);
+            while (0..8).contains(&cx) && (0..8).contains(&cy) {
+                let square = (cy * 8 + cx) as usize;
+                match board.get(square) {
+                    Some(disc) if disc == player => {
+                        flips.append(&mut line);
+                        break;
+                    }
+                    Some(_) => line.push(square),
+                    None => break,
+                }
+                cx += dx;
+                cy += dy;
+            }
+        }
+
+        flips.sort_unstable();
+        flips
+    }
+
+    /// Every square `player` can move to, in index order.
+    pub fn moves(board: &Board, player: Disc) -> Vec<usize> {
+        (0..64)
+            .filter(|&index| !flips(board, player, index).is_empty())
+            .collect()
+    }
+}
+
+/// Maps an (x, y) square to its image under one of the board's symmetries.
+type Symmetry = fn(usize, usize) -> (usize, usize);
+
+/// The eight symmetries of the board.
+const SYMMETRIES: [Symmetry; 8] = [
+    |x, y| (x, y),
+    |x, y| (7 - x, y),
+    |x, y| (x, 7 - y),
+    |x, y| (7 - x, 7 - y),
+    |x, y| (y, x),
+    |x, y| (7 - y, x),
+    |x, y| (y, 7 - x),
+    |x, y| (7 - y, 7 - x),
+];
+
+fn transform(symmetry: Symmetry, index: usize) -> usize {
+    let (x, y) = symmetry(index % 8, index / 8);
+    y * 8 + x
+}
+
+fn disc(dark: bool) -> Disc {
+    if dark {
+        Disc::Dark
+    } else {
+        Disc::Light
+    }
+}
+
+/// Plays a game out from the opening, picking each move with the next of `choices`, and
+/// passing only when forced. Stops when the choices run out or neither player can move.
+fn play_out(choices: &[Index]) -> Game {
+    let mut game = Game::new();
+
+    for choice in choices {
+        let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+        if !moves.is_empty() {
+            game.play(moves[choice.index(moves.len())]);
+        } else if game.valid_moves(game.turn.opposite()).next().is_some() {
+            game.pass();
+        } else {
+            break;
+        }
+    }
+
+    game
+}
+
+/// A legal game, anywhere from the opening to the end.
+fn games() -> impl Strategy<Value = Game> {
+    prop::collection::vec(any::<Index>(), 0..70).prop_map(|choices| play_out(&choices))
+}
+
+/// Any board at all, most of them unreachable in a real game.
+fn boards() -> impl Strategy<Value = Board> {
+    prop::collection::vec(prop::option::of(any::<bool>()), 64).prop_map(|squares| {
+        let mut board = Board::default();
+        for (index, square) in squares.into_iter().enumerate() {
+            if let Some(dark) = square {
+                board.set(index, disc(dark));
+            }
+        }
+        board
+    })
+}
+
+proptest! {
+    #[test]
+    fn scores_add_up(game in games()) {
+        prop_assert_eq!(game.dark + game.light + game.empty, 64);
+        prop_assert_eq!(game.dark, game.board.indexes_of(Some(Disc::Dark)).count());
+        prop_assert_eq!(game.light, game.board.indexes_of(Some(Disc::Light)).count());
+        prop_assert_eq!(game.empty, game.board.indexes_of(None).count());
+    }
+
+    #[test]
+    fn moves_match_the_reference(game in games()) {
+        for player in [Disc::Dark, Disc::Light] {
+            let moves: Vec<usize> = game.valid_moves(player).collect();
+            prop_assert_eq!(moves, naive::moves(&game.board, player));
+        }
+
+        for index in game.board.indexes_of(None).collect::<Vec<_>>() {
+            let mut flips = game.flips_for(index);
+            flips.sort_unstable();
+            prop_assert_eq!(flips, naive::flips(&game.board, game.turn, index));
+        }
+    }
+
+    #[test]
+    fn moves_match_the_reference_on_any_board(board in boards(), dark in any::<bool>()) {
+        let game = Game::from_position(board, disc(dark));
+        let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+        prop_assert_eq!(moves, naive::moves(&board, game.turn));
+
+        for index in board.indexes_of(None).collect::<Vec<_>>() {
+            let mut flips = game.flips_for(index);
+            flips.sort_unstable();
+            prop_assert_eq!(flips, naive::flips(&board, game.turn, index));
+        }
+    }
+
+    #[test]
+    fn playing_flips_exactly_the_reference_discs(game in games(), choice in any::<Index>()) {
+        let moves: Vec<usize> = game.valid_moves(game.turn).collect();
+        prop_assume!(!moves.is_empty());
+
+        let index = moves[choice.index(moves.len())];
+        let flips = naive::flips(&game.board, game.turn, index);
+        let mut played = game.clone();
+        played.play(index);
+
+        for square in 0..64 {
+            let expected = if square == index || flips.contains(&square) {
+                Some(game.turn)
+            } else {
+                game.board.get(square)
+            };
+            prop_assert_eq!(played.board.get(square), expected);
+        }
+        prop_assert_eq!(played.turn, game.turn.opposite());
+        prop_assert_eq!(played.empty, game.empty - 1);
+    }
+
+    #[test]
+    fn symmetric_positions_have_symmetric_moves(board in boards(), dark in any::<bool>()) {
+        let player = disc(dark);
+        let game = Game::from_position(board, player);
+        let moves: Vec<usize> = game.valid_moves(player).collect();
+
+        for symmetry in SYMMETRIES {
+            let mut mirrored = Board::default();
+            for index in 0..64 {
+                if let Some(disc) = board.get(index) {
+                    mirrored.set(transform(symmetry, index), disc);
+                }
+            }
+
+            let mut expected: Vec<usize> = moves.iter().map(|&m| transform(symmetry, m)).collect();
+            expected.sort_unstable();
+            let mirrored_moves: Vec<usize> =
+                Game::from_position(mirrored, player).valid_moves(player).collect();
+            prop_assert_eq!(mirrored_moves, expected);
+        }
+    }
+
+    #[test]
+    fn transcripts_round_trip(game in games()) {
+        let replayed = Game::from_transcript(&game.transcript).expect("a legal transcript");
+
+        prop_assert_eq!(replayed.board, game.board);
+        prop_assert_eq!(replayed.turn, game.turn);
+        prop_assert_eq!(&replayed.transcript, &game.transcript);
+        prop_assert_eq!(
+            (replayed.dark, replayed.light, replayed.empty),
+            (game.dark, game.light, game.empty)
+        );
+
+        // replaying also notices a game that's over, even without the closing passes
+        let over = game.valid_moves(Disc::Dark).next().is_none()
+            && game.valid_moves(Disc::Light).next().is_none();
+        prop_assert_eq!(replayed.is_complete, over);
+
+        // and the transcript survives being written out and parsed back
+        let written: Vec<String> = game.transcript.iter().map(Play::to_string).collect();
+        let parsed = desdemona::parse_transcript(&written.join(",")).unwrap();
+        prop_assert_eq!(parsed, game.transcript);
+    }
+}